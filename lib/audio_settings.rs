@@ -0,0 +1,107 @@
+use crate::data_file::{load_or_default, write_atomically};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::Path;
+
+pub const AUDIO_SETTINGS_FILE: &str = "audio_settings.toml";
+// How much one press of the volume keys changes a channel
+pub const VOLUME_STEP: f32 = 0.1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Channel {
+    Master,
+    Music,
+    Effects,
+}
+
+// Volume of each channel from 0.0 to 1.0; music and effects are scaled by master
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub master: f32,
+    pub music: f32,
+    pub effects: f32,
+    pub muted: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master: 1.0,
+            music: 0.5,
+            effects: 1.0,
+            muted: false,
+        }
+    }
+}
+
+impl AudioSettings {
+    pub fn volume(&self, channel: Channel) -> f32 {
+        match channel {
+            Channel::Master => self.master,
+            Channel::Music => self.music,
+            Channel::Effects => self.effects,
+        }
+    }
+
+    // Snaps to whole steps so repeated presses land back on round numbers
+    pub fn adjust(&mut self, channel: Channel, delta: f32) {
+        let volume = match channel {
+            Channel::Master => &mut self.master,
+            Channel::Music => &mut self.music,
+            Channel::Effects => &mut self.effects,
+        };
+        *volume = ((*volume + delta) / VOLUME_STEP).round() * VOLUME_STEP;
+        *volume = volume.clamp(0.0, 1.0);
+    }
+
+    pub fn toggle_mute(&mut self) {
+        self.muted = !self.muted;
+    }
+
+    // What the sources are actually set to
+    pub fn music_volume(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.master * self.music
+        }
+    }
+
+    pub fn effects_volume(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.master * self.effects
+        }
+    }
+
+    // Out of range or non-numeric volumes are pulled back into 0.0..=1.0
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut settings: Self = toml::from_str(text).ok()?;
+        for volume in [
+            &mut settings.master,
+            &mut settings.music,
+            &mut settings.effects,
+        ] {
+            *volume = if volume.is_finite() {
+                volume.clamp(0.0, 1.0)
+            } else {
+                1.0
+            };
+        }
+        Some(settings)
+    }
+
+    pub fn to_file_string(&self) -> String {
+        toml::to_string(self).expect("audio settings always serialize")
+    }
+
+    pub fn load(path: &Path) -> Self {
+        load_or_default(path, "audio settings", Self::parse)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        write_atomically(path, self.to_file_string())
+    }
+}
@@ -0,0 +1,136 @@
+use crate::game::{Ball, Bar, Input, Simulation, TICK_DT};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::VecDeque;
+
+// Preset knobs for the autopilot, from forgiving to nearly perfect
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    // Ticks between the ball doing something and the autopilot reacting to it
+    pub fn reaction_ticks(self) -> usize {
+        match self {
+            Difficulty::Easy => 20,
+            Difficulty::Normal => 10,
+            Difficulty::Hard => 3,
+        }
+    }
+
+    // How far off centre it may aim, as a fraction of half the bar; past 1.0
+    // it can miss outright
+    pub fn error(self) -> f32 {
+        match self {
+            Difficulty::Easy => 1.3,
+            Difficulty::Normal => 0.7,
+            Difficulty::Hard => 0.2,
+        }
+    }
+}
+
+// Plays the bottom bar: works out where the ball will come down and holds
+// left or right until the bar is under it. It produces the same Input a
+// player would, so its runs can be recorded and replayed like any other.
+pub struct Autopilot {
+    pub reaction_ticks: usize,
+    pub error: f32,
+    rng: StdRng,
+    seen: VecDeque<Vec<Ball>>, // what the balls looked like over the last reaction_ticks
+    aim: f32,                  // offset from the bar's centre for this approach
+    descending: bool,
+}
+
+impl Autopilot {
+    pub fn new(difficulty: Difficulty, seed: u64) -> Self {
+        Self {
+            reaction_ticks: difficulty.reaction_ticks(),
+            error: difficulty.error(),
+            rng: StdRng::seed_from_u64(seed),
+            seen: VecDeque::new(),
+            aim: 0.0,
+            descending: false,
+        }
+    }
+
+    // Input for the next tick, judged on the balls as they were reaction_ticks ago
+    pub fn next_input(&mut self, sim: &Simulation) -> Input {
+        self.seen.push_back(sim.balls.clone());
+        while self.seen.len() > self.reaction_ticks + 1 {
+            self.seen.pop_front();
+        }
+        let bar = &sim.bar;
+        let wall_above = sim.player_two.is_none();
+        let crossing = self.seen[0]
+            .iter()
+            .filter_map(|ball| {
+                predict_crossing(ball, bar.y, sim.config.width, wall_above)
+                    .map(|(secs, x)| (secs, x + ball.size / 2.0))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+
+        // A new approach gets a new aiming mistake
+        let descending = crossing.is_some();
+        if descending && !self.descending {
+            self.aim = self.rng.gen_range(-1.0..=1.0) * self.error * bar.width / 2.0;
+        }
+        self.descending = descending;
+
+        // With nothing coming, wait in the middle
+        let target = match crossing {
+            Some((_, x)) => x + self.aim,
+            None => sim.config.width / 2.0,
+        };
+        steer_toward(bar, target)
+    }
+}
+
+// Hold a direction until the bar's centre is within one tick's travel of `x`
+fn steer_toward(bar: &Bar, x: f32) -> Input {
+    let offset = x - (bar.x + bar.width / 2.0);
+    let step = bar.speed * TICK_DT;
+    Input {
+        left: offset < -step,
+        right: offset > step,
+        ..Input::default()
+    }
+}
+
+// Seconds until the ball's bottom edge reaches `bar_y` and the ball's x then,
+// bouncing off the side walls on the way. A rising ball is followed off the
+// top wall when there is one. Bricks and obstacles aren't taken into account.
+pub fn predict_crossing(
+    ball: &Ball,
+    bar_y: f32,
+    field_width: f32,
+    wall_above: bool,
+) -> Option<(f32, f32)> {
+    let fall = bar_y - (ball.y + ball.size);
+    let distance = if ball.dy > 0.0 {
+        fall
+    } else if ball.dy < 0.0 && wall_above {
+        ball.y * 2.0 + fall
+    } else {
+        return None;
+    };
+    if distance < 0.0 {
+        return None;
+    }
+    let seconds = distance / ball.dy.abs();
+    Some((
+        seconds,
+        fold(ball.x + ball.dx * seconds, field_width - ball.size),
+    ))
+}
+
+// Bring a straight-line x back into 0..=span, as bouncing between walls would
+fn fold(x: f32, span: f32) -> f32 {
+    if span <= 0.0 {
+        return 0.0;
+    }
+    let x = x.rem_euclid(span * 2.0);
+    if x > span { span * 2.0 - x } else { x }
+}
@@ -0,0 +1,343 @@
+use ggez::input::gamepad::gilrs::Button;
+use ggez::input::keyboard::{KeyCode, KeyboardContext};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Everything a key can do; what is bound to each lives in KeyBindings
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    Pause,
+    Fullscreen,
+    Restart,
+    Rebind,
+    Mute,
+    VolumeDown,
+    VolumeUp,
+    Editor,
+    PlayerTwoLeft,
+    PlayerTwoRight,
+    Menu,
+    MenuUp,
+    MenuDown,
+    MenuSelect,
+}
+
+impl Action {
+    pub const ALL: [Action; 16] = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Pause,
+        Action::Fullscreen,
+        Action::Restart,
+        Action::Rebind,
+        Action::Mute,
+        Action::VolumeDown,
+        Action::VolumeUp,
+        Action::Editor,
+        Action::PlayerTwoLeft,
+        Action::PlayerTwoRight,
+        Action::Menu,
+        Action::MenuUp,
+        Action::MenuDown,
+        Action::MenuSelect,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::MoveLeft => "Move Left",
+            Action::MoveRight => "Move Right",
+            Action::Pause => "Pause",
+            Action::Fullscreen => "Fullscreen",
+            Action::Restart => "Retry",
+            Action::Rebind => "Rebind Keys",
+            Action::Mute => "Mute",
+            Action::VolumeDown => "Volume Down",
+            Action::VolumeUp => "Volume Up",
+            Action::Editor => "Level Editor",
+            Action::PlayerTwoLeft => "Player 2 Left",
+            Action::PlayerTwoRight => "Player 2 Right",
+            Action::Menu => "Main Menu",
+            Action::MenuUp => "Menu Up",
+            Action::MenuDown => "Menu Down",
+            Action::MenuSelect => "Menu Select",
+        }
+    }
+}
+
+// Gamepad buttons aren't rebindable; the d-pad moves the bar (see Game::pad)
+pub fn gamepad_action(button: Button) -> Option<Action> {
+    match button {
+        Button::Start => Some(Action::Pause),
+        Button::South => Some(Action::Restart),
+        Button::Select => Some(Action::Fullscreen),
+        Button::East => Some(Action::Menu),
+        _ => None,
+    }
+}
+
+// Key names as written in the config file, which are the KeyCode variant names
+macro_rules! key_names {
+    ($($key:ident),* $(,)?) => {
+        pub fn key_name(key: KeyCode) -> Option<&'static str> {
+            match key {
+                $(KeyCode::$key => Some(stringify!($key)),)*
+                _ => None,
+            }
+        }
+
+        pub fn key_from_name(name: &str) -> Option<KeyCode> {
+            match name {
+                $(stringify!($key) => Some(KeyCode::$key),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+key_names![
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+    T,
+    U,
+    V,
+    W,
+    X,
+    Y,
+    Z,
+    Key0,
+    Key1,
+    Key2,
+    Key3,
+    Key4,
+    Key5,
+    Key6,
+    Key7,
+    Key8,
+    Key9,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    Left,
+    Right,
+    Up,
+    Down,
+    Space,
+    Return,
+    Escape,
+    Tab,
+    Back,
+    Insert,
+    Delete,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Numpad0,
+    Numpad1,
+    Numpad2,
+    Numpad3,
+    Numpad4,
+    Numpad5,
+    Numpad6,
+    Numpad7,
+    Numpad8,
+    Numpad9,
+    NumpadAdd,
+    NumpadSubtract,
+    NumpadEnter,
+    LShift,
+    RShift,
+    LControl,
+    RControl,
+    LAlt,
+    RAlt,
+    Comma,
+    Period,
+    Slash,
+    Semicolon,
+    Apostrophe,
+    LBracket,
+    RBracket,
+    Minus,
+    Equals,
+    Grave,
+    Backslash
+];
+
+// Which keys trigger which action. In the config file this is a table of
+// action names to key names, e.g. `move_left = ["Left", "A"]`; actions left
+// out keep their default keys.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(
+    try_from = "BTreeMap<Action, Vec<String>>",
+    into = "BTreeMap<Action, Vec<String>>"
+)]
+pub struct KeyBindings {
+    keys: BTreeMap<Action, Vec<KeyCode>>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let keys = BTreeMap::from([
+            (Action::MoveLeft, vec![KeyCode::Left, KeyCode::A]),
+            (Action::MoveRight, vec![KeyCode::Right, KeyCode::D]),
+            (Action::Pause, vec![KeyCode::P, KeyCode::Space]),
+            (Action::Fullscreen, vec![KeyCode::F]),
+            (Action::Restart, vec![KeyCode::R]),
+            (Action::Rebind, vec![KeyCode::K]),
+            (Action::Mute, vec![KeyCode::M]),
+            (Action::VolumeDown, vec![KeyCode::Minus]),
+            (Action::VolumeUp, vec![KeyCode::Equals]),
+            (Action::Editor, vec![KeyCode::E]),
+            (Action::PlayerTwoLeft, vec![KeyCode::Comma]),
+            (Action::PlayerTwoRight, vec![KeyCode::Period]),
+            (Action::Menu, vec![KeyCode::Escape]),
+            (Action::MenuUp, vec![KeyCode::Up, KeyCode::W]),
+            (Action::MenuDown, vec![KeyCode::Down, KeyCode::S]),
+            (
+                Action::MenuSelect,
+                vec![KeyCode::Return, KeyCode::NumpadEnter],
+            ),
+        ]);
+        Self { keys }
+    }
+}
+
+impl TryFrom<BTreeMap<Action, Vec<String>>> for KeyBindings {
+    type Error = String;
+
+    fn try_from(names: BTreeMap<Action, Vec<String>>) -> Result<Self, Self::Error> {
+        let mut listed = Self {
+            keys: BTreeMap::new(),
+        };
+        for (action, names) in names {
+            let keys = names
+                .iter()
+                .map(|name| {
+                    key_from_name(name)
+                        .ok_or_else(|| format!("unknown key '{}' for {:?}", name, action))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            listed.keys.insert(action, keys);
+        }
+        // Only the file can contradict itself; a key it gives one action is
+        // taken from the defaults of the others, as `rebind` does
+        if let Some((key, first, second)) = listed.conflict() {
+            return Err(format!(
+                "{} is bound to both {:?} and {:?}",
+                key_name(key).unwrap_or("key"),
+                first,
+                second
+            ));
+        }
+        let mut bindings = Self::default();
+        for keys in bindings.keys.values_mut() {
+            keys.retain(|key| listed.action_for(*key).is_none());
+        }
+        bindings.keys.extend(listed.keys);
+        Ok(bindings)
+    }
+}
+
+impl From<KeyBindings> for BTreeMap<Action, Vec<String>> {
+    fn from(bindings: KeyBindings) -> Self {
+        bindings
+            .keys
+            .into_iter()
+            .map(|(action, keys)| {
+                let names = keys
+                    .into_iter()
+                    .filter_map(key_name)
+                    .map(str::to_string)
+                    .collect();
+                (action, names)
+            })
+            .collect()
+    }
+}
+
+impl KeyBindings {
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        self.keys.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn action_for(&self, key: KeyCode) -> Option<Action> {
+        self.keys
+            .iter()
+            .find(|(_, keys)| keys.contains(&key))
+            .map(|(&action, _)| action)
+    }
+
+    pub fn is_held(&self, action: Action, keyboard: &KeyboardContext) -> bool {
+        self.keys(action)
+            .iter()
+            .any(|&key| keyboard.is_key_pressed(key))
+    }
+
+    // Make `key` the only key for `action`, taking it away from whatever had it before
+    pub fn rebind(&mut self, action: Action, key: KeyCode) {
+        for keys in self.keys.values_mut() {
+            keys.retain(|&k| k != key);
+        }
+        self.keys.insert(action, vec![key]);
+    }
+
+    // A key bound to two actions, if there is one
+    pub fn conflict(&self) -> Option<(KeyCode, Action, Action)> {
+        for (&first, keys) in &self.keys {
+            for &key in keys {
+                if let Some((&second, _)) = self
+                    .keys
+                    .iter()
+                    .find(|&(&other, other_keys)| other > first && other_keys.contains(&key))
+                {
+                    return Some((key, first, second));
+                }
+            }
+        }
+        None
+    }
+
+    // "Left/A" style listing for the HUD
+    pub fn describe(&self, action: Action) -> String {
+        let names: Vec<&str> = self
+            .keys(action)
+            .iter()
+            .filter_map(|&key| key_name(key))
+            .collect();
+        if names.is_empty() {
+            "unbound".to_string()
+        } else {
+            names.join("/")
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::config::GameConfig;
+use crate::physics::Aabb;
+
+// Space between bricks and between the grid and the side walls
+pub const BRICK_GAP: f32 = 4.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Brick {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub hp: u32,
+    pub max_hp: u32,
+    pub score: usize, // awarded when the brick breaks
+}
+
+impl Brick {
+    pub fn new(x: f32, y: f32, width: f32, height: f32, hp: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            hp,
+            max_hp: hp,
+            score: hp as usize,
+        }
+    }
+
+    pub fn aabb(&self) -> Aabb {
+        Aabb::new(self.x, self.y, self.width, self.height)
+    }
+
+    // Returns true once the brick has no hit points left
+    pub fn hit(&mut self) -> bool {
+        self.hp = self.hp.saturating_sub(1);
+        self.hp == 0
+    }
+}
+
+// Where the grid may go: from a tenth of the way down to just above where the ball spawns
+pub fn brick_area(config: &GameConfig) -> Aabb {
+    let top = config.height / 10.0;
+    let bottom = config.height / 3.0 - config.ball_size;
+    Aabb::new(0.0, top, config.width, bottom - top)
+}
+
+// Cell `row`, `column` of a `rows` x `columns` grid spread over the brick area
+pub fn grid_cell(
+    config: &GameConfig,
+    rows: usize,
+    columns: usize,
+    row: usize,
+    column: usize,
+) -> Aabb {
+    let area = brick_area(config);
+    let width = (area.w - BRICK_GAP * (columns + 1) as f32) / columns as f32;
+    let height = (area.h - BRICK_GAP * (rows + 1) as f32) / rows as f32;
+    Aabb::new(
+        area.x + BRICK_GAP + column as f32 * (width + BRICK_GAP),
+        area.y + BRICK_GAP + row as f32 * (height + BRICK_GAP),
+        width,
+        height,
+    )
+}
+
+// The grid for a stage: rows nearer the top are tougher, and every stage
+// adds a hit point to every brick
+pub fn brick_grid(config: &GameConfig, stage: usize) -> Vec<Brick> {
+    let (rows, columns) = (config.brick_rows, config.brick_columns);
+    if rows == 0 || columns == 0 {
+        return Vec::new();
+    }
+    let mut bricks = Vec::with_capacity(rows * columns);
+    for row in 0..rows {
+        let hp = ((rows - 1 - row) / 2 + stage.max(1)) as u32;
+        for column in 0..columns {
+            let cell = grid_cell(config, rows, columns, row, column);
+            bricks.push(Brick::new(cell.x, cell.y, cell.w, cell.h, hp));
+        }
+    }
+    bricks
+}
@@ -0,0 +1,111 @@
+use ggez::graphics::Rect;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::f32::consts::PI;
+
+// Furthest the view is thrown at full trauma
+pub const MAX_SHAKE_OFFSET: f32 = 24.0; // pixels
+// Trauma lost per second, so a full shake settles in under a second
+pub const TRAUMA_DECAY: f32 = 1.5;
+pub const ZOOM_PULSE_SCALE: f32 = 0.08; // extra zoom at the height of the pulse
+pub const ZOOM_PULSE_SECS: f32 = 0.6;
+pub const HEART_LOST_TRAUMA: f32 = 0.6;
+pub const GAME_OVER_TRAUMA: f32 = 0.8;
+// Bricks broken without the ball touching a bar; from COMBO_SHAKE_MIN on
+// each one knocks the camera
+pub const COMBO_SHAKE_MIN: usize = 3;
+pub const COMBO_TRAUMA: f32 = 0.25;
+
+// Moves the view of the field without touching the simulation. Shake follows
+// the "trauma" model: hits add trauma, which wears off over time, and the
+// shake grows with its square so small knocks stay subtle. When disabled it
+// never moves.
+pub struct Camera {
+    pub enabled: bool,
+    trauma: f32,
+    pulse: Option<f32>, // seconds into the zoom pulse
+    offset: [f32; 2],
+    rng: StdRng,
+}
+
+impl Camera {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            trauma: 0.0,
+            pulse: None,
+            offset: [0.0, 0.0],
+            rng: StdRng::seed_from_u64(0),
+        }
+    }
+
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    // `amount` between 0 and 1; trauma is capped at 1
+    pub fn add_trauma(&mut self, amount: f32) {
+        if self.enabled {
+            self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+        }
+    }
+
+    pub fn pulse_zoom(&mut self) {
+        if self.enabled {
+            self.pulse = Some(0.0);
+        }
+    }
+
+    // Stop any shake or pulse at once
+    pub fn settle(&mut self) {
+        self.trauma = 0.0;
+        self.pulse = None;
+        self.offset = [0.0, 0.0];
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.trauma = (self.trauma - TRAUMA_DECAY * dt).max(0.0);
+        self.pulse = self
+            .pulse
+            .map(|elapsed| elapsed + dt)
+            .filter(|&elapsed| elapsed < ZOOM_PULSE_SECS);
+        let shake = self.trauma * self.trauma * MAX_SHAKE_OFFSET;
+        self.offset = [
+            self.rng.gen_range(-1.0..=1.0) * shake,
+            self.rng.gen_range(-1.0..=1.0) * shake,
+        ];
+    }
+
+    pub fn offset(&self) -> [f32; 2] {
+        self.offset
+    }
+
+    // Swells in and back out over the pulse
+    pub fn zoom(&self) -> f32 {
+        match self.pulse {
+            Some(elapsed) => 1.0 + ZOOM_PULSE_SCALE * (PI * elapsed / ZOOM_PULSE_SECS).sin(),
+            None => 1.0,
+        }
+    }
+
+    // The part of `screen` to show: zoomed about its centre and moved by the shake
+    pub fn view(&self, screen: Rect) -> Rect {
+        let zoom = self.zoom();
+        let (w, h) = (screen.w / zoom, screen.h / zoom);
+        Rect::new(
+            screen.x + (screen.w - w) / 2.0 - self.offset[0],
+            screen.y + (screen.h - h) / 2.0 - self.offset[1],
+            w,
+            h,
+        )
+    }
+
+    // Where a point on `screen`, such as the mouse, lands in what `view` shows
+    pub fn view_point(&self, screen: Rect, [x, y]: [f32; 2]) -> [f32; 2] {
+        let view = self.view(screen);
+        [
+            view.x + (x - screen.x) / screen.w * view.w,
+            view.y + (y - screen.y) / screen.h * view.h,
+        ]
+    }
+}
@@ -0,0 +1,220 @@
+use crate::bindings::KeyBindings;
+use crate::bricks::{BRICK_GAP, brick_area};
+use crate::levels::{LEVELS_DIR, Level};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_FILE: &str = "bounce_shield.toml";
+// Space between the bottom of the field and the bar
+pub const BAR_MARGIN: f32 = 10.0;
+
+// Tuning values for the field, ball, bar and rules; every field can be left
+// out of the config file to keep its default
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameConfig {
+    pub width: f32,
+    pub height: f32,
+    pub ball_size: f32,
+    pub ball_speed: f32, // pixels per second
+    pub bar_width: f32,
+    pub bar_height: f32,
+    pub bar_speed: f32,     // pixels per second
+    pub pointer_speed: f32, // fastest the bar follows the mouse, pixels per second
+    pub initial_hearts: usize,
+    pub max_bounce_angle: f32,   // degrees off vertical
+    pub min_vertical_speed: f32, // pixels per second
+    pub power_up_chance: f32,    // per landing on the bar, 0 turns power-ups off
+    pub brick_rows: usize,       // 0 plays without bricks
+    pub brick_columns: usize,
+    pub two_player: bool, // a second bar at the top, for two players on one machine
+    pub screen_shake: bool, // false keeps the view still for players sensitive to motion
+    // Level files are played from here when it exists; "" keeps to the brick rows
+    pub levels_dir: PathBuf,
+    pub bindings: KeyBindings,
+    // Read from `levels_dir` rather than the config file; when there are any
+    // they replace the generated brick rows
+    #[serde(skip)]
+    pub levels: Vec<Level>,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            width: 1920.0,
+            height: 1080.0,
+            ball_size: 20.0,
+            ball_speed: 300.0,
+            bar_width: 150.0,
+            bar_height: 20.0,
+            bar_speed: 600.0,
+            pointer_speed: 600.0,
+            initial_hearts: 3,
+            max_bounce_angle: 60.0,
+            min_vertical_speed: 150.0,
+            power_up_chance: 0.1,
+            brick_rows: 0,
+            brick_columns: 10,
+            two_player: false,
+            screen_shake: true,
+            levels_dir: PathBuf::from(LEVELS_DIR),
+            bindings: KeyBindings::default(),
+            levels: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(PathBuf, io::Error),
+    Parse(toml::de::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, e) => write!(f, "failed to read {}: {}", path.display(), e),
+            ConfigError::Parse(e) => write!(f, "invalid config: {}", e),
+            ConfigError::Invalid(reason) => write!(f, "invalid config: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl GameConfig {
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let text = fs::read_to_string(path).map_err(|e| ConfigError::Io(path.to_path_buf(), e))?;
+        Self::from_toml(&text)
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, ConfigError> {
+        let config: Self = toml::from_str(text).map_err(ConfigError::Parse)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("config always serializes")
+    }
+
+    // Fingerprint of everything that affects how a run plays out. Hand-rolled
+    // FNV-1a rather than std's DefaultHasher so it is stable across builds,
+    // which replays rely on to detect they were recorded with other tuning.
+    pub fn gameplay_hash(&self) -> u64 {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let mut feed = |bytes: &[u8]| {
+            for &byte in bytes {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+            }
+        };
+        for value in [
+            self.width,
+            self.height,
+            self.ball_size,
+            self.ball_speed,
+            self.bar_width,
+            self.bar_height,
+            self.bar_speed,
+            self.pointer_speed,
+            self.max_bounce_angle,
+            self.min_vertical_speed,
+            self.power_up_chance,
+        ] {
+            feed(&value.to_le_bytes());
+        }
+        for value in [self.initial_hearts, self.brick_rows, self.brick_columns] {
+            feed(&(value as u64).to_le_bytes());
+        }
+        for level in &self.levels {
+            feed(&level.gameplay_bytes());
+        }
+        // Only fed when on, so one-player hashes stay what they were
+        if self.two_player {
+            feed(b"two_player");
+        }
+        hash
+    }
+
+    pub fn max_bounce_angle_radians(&self) -> f32 {
+        self.max_bounce_angle.to_radians()
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let invalid = |reason: String| Err(ConfigError::Invalid(reason));
+        let positive = [
+            ("width", self.width),
+            ("height", self.height),
+            ("ball_size", self.ball_size),
+            ("ball_speed", self.ball_speed),
+            ("bar_width", self.bar_width),
+            ("bar_height", self.bar_height),
+            ("bar_speed", self.bar_speed),
+            ("pointer_speed", self.pointer_speed),
+        ];
+        for (name, value) in positive {
+            if !value.is_finite() || value <= 0.0 {
+                return invalid(format!("{} must be a positive number, got {}", name, value));
+            }
+        }
+        if self.bar_width > self.width {
+            return invalid(format!(
+                "bar_width {} is wider than the field ({})",
+                self.bar_width, self.width
+            ));
+        }
+        if self.ball_size * 2.0 >= self.width {
+            return invalid(format!(
+                "ball_size {} leaves no room to spawn in a field {} wide",
+                self.ball_size, self.width
+            ));
+        }
+        if self.bar_height + BAR_MARGIN + self.ball_size >= self.height / 3.0 * 2.0 {
+            return invalid(format!(
+                "height {} is too short for the bar and ball",
+                self.height
+            ));
+        }
+        if self.initial_hearts == 0 {
+            return invalid("initial_hearts must be at least 1".to_string());
+        }
+        if !(self.max_bounce_angle > 0.0 && self.max_bounce_angle < 90.0) {
+            return invalid(format!(
+                "max_bounce_angle must be between 0 and 90 degrees, got {}",
+                self.max_bounce_angle
+            ));
+        }
+        if !self.min_vertical_speed.is_finite() || self.min_vertical_speed < 0.0 {
+            return invalid(format!(
+                "min_vertical_speed must not be negative, got {}",
+                self.min_vertical_speed
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.power_up_chance) {
+            return invalid(format!(
+                "power_up_chance must be between 0 and 1, got {}",
+                self.power_up_chance
+            ));
+        }
+        if self.brick_rows > 0 {
+            let area = brick_area(self);
+            let columns = self.brick_columns as f32;
+            let rows = self.brick_rows as f32;
+            if self.brick_columns == 0
+                || (area.w - BRICK_GAP * (columns + 1.0)) / columns < 1.0
+                || (area.h - BRICK_GAP * (rows + 1.0)) / rows < 1.0
+            {
+                return invalid(format!(
+                    "{} rows of {} bricks don't fit in the field",
+                    self.brick_rows, self.brick_columns
+                ));
+            }
+        }
+        Ok(())
+    }
+}
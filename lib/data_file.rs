@@ -0,0 +1,37 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+// The records and settings kept in the user data directory. A missing file
+// just means the defaults, and so does one `parse` turns down, after a
+// warning naming `what` was in it.
+pub fn load_or_default<T: Default>(
+    path: &Path,
+    what: &str,
+    parse: impl FnOnce(&str) -> Option<T>,
+) -> T {
+    match fs::read_to_string(path) {
+        Ok(text) => parse(&text).unwrap_or_else(|| {
+            eprintln!("Ignoring corrupt {} file {}", what, path.display());
+            T::default()
+        }),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => T::default(),
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", path.display(), e);
+            T::default()
+        }
+    }
+}
+
+// Written to a temporary file first and renamed into place, so a crash
+// mid-write can't leave a half-written file behind
+pub fn write_atomically(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp_path = path.with_extension("tmp");
+    let mut file = fs::File::create(&tmp_path)?;
+    file.write_all(contents.as_ref())?;
+    file.sync_all()?;
+    fs::rename(&tmp_path, path)
+}
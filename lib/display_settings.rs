@@ -0,0 +1,38 @@
+use crate::data_file::{load_or_default, write_atomically};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::Path;
+
+pub const DISPLAY_SETTINGS_FILE: &str = "display_settings.toml";
+
+// Choices made on the Settings page that aren't audio. `screen_shake = false`
+// in the config still wins at startup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisplaySettings {
+    pub screen_shake: bool,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self { screen_shake: true }
+    }
+}
+
+impl DisplaySettings {
+    pub fn parse(text: &str) -> Option<Self> {
+        toml::from_str(text).ok()
+    }
+
+    pub fn to_file_string(&self) -> String {
+        toml::to_string(self).expect("display settings always serialize")
+    }
+
+    pub fn load(path: &Path) -> Self {
+        load_or_default(path, "display settings", Self::parse)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        write_atomically(path, self.to_file_string())
+    }
+}
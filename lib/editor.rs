@@ -0,0 +1,154 @@
+use crate::bricks::grid_cell;
+use crate::config::GameConfig;
+use crate::data_file::write_atomically;
+use crate::levels::{Cell, Level, LevelError};
+use crate::physics::Aabb;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Saved next to the other levels so the next start picks it up
+pub const EDITOR_FILE: &str = "custom.txt";
+// Smallest grid the editor shows; levels are saved at the size shown
+pub const EDITOR_ROWS: usize = 6;
+pub const EDITOR_COLUMNS: usize = 12;
+pub const BALL_SPEED_STEP: f32 = 20.0;
+pub const BAR_WIDTH_STEP: f32 = 10.0;
+
+// A level being edited: the grid on screen is at least the editor size so
+// every cell can be clicked, while the layout only grows to the cells painted.
+// It is padded out to the grid shown when saved or play-tested, so it plays
+// exactly as drawn. Parameters left unset fall back to the config.
+pub struct Editor {
+    pub level: Level,
+    pub path: PathBuf,
+    pub brush: Cell,
+}
+
+impl Editor {
+    pub fn new(level: Level, path: PathBuf) -> Self {
+        Self {
+            level,
+            path,
+            brush: Cell::Brick(1),
+        }
+    }
+
+    // Edit the level at `path`, or start a blank one if there is no file yet
+    pub fn open(path: &Path, config: &GameConfig) -> Result<Self, LevelError> {
+        let level = match Level::load(path, config) {
+            Ok(level) => level,
+            Err(LevelError::Io(_, e)) if e.kind() == io::ErrorKind::NotFound => Level {
+                name: "Custom".to_string(),
+                ball_speed: None,
+                bar_width: None,
+                hearts: None,
+                layout: Vec::new(),
+            },
+            Err(e) => return Err(e),
+        };
+        Ok(Self::new(level, path.to_path_buf()))
+    }
+
+    pub fn rows(&self) -> usize {
+        self.level.layout.len().max(EDITOR_ROWS)
+    }
+
+    pub fn columns(&self) -> usize {
+        self.level.columns().max(EDITOR_COLUMNS)
+    }
+
+    // Cells of the grid outside the level's layout are empty
+    pub fn cell(&self, row: usize, column: usize) -> Cell {
+        self.level
+            .layout
+            .get(row)
+            .and_then(|cells| cells.get(column))
+            .copied()
+            .unwrap_or(Cell::Empty)
+    }
+
+    pub fn cell_area(&self, config: &GameConfig, row: usize, column: usize) -> Aabb {
+        grid_cell(config, self.rows(), self.columns(), row, column)
+    }
+
+    // The grid cell under a screen position, if any
+    pub fn cell_at(&self, config: &GameConfig, x: f32, y: f32) -> Option<(usize, usize)> {
+        let point = Aabb::new(x, y, 0.0, 0.0);
+        (0..self.rows())
+            .flat_map(|row| (0..self.columns()).map(move |column| (row, column)))
+            .find(|&(row, column)| {
+                let area = self.cell_area(config, row, column);
+                point.x >= area.x
+                    && point.x < area.x + area.w
+                    && point.y >= area.y
+                    && point.y < area.y + area.h
+            })
+    }
+
+    // Painting outside the layout grows it to a rectangle reaching the cell,
+    // so no row is left blank in the saved file
+    pub fn paint(&mut self, row: usize, column: usize) {
+        if row >= self.level.layout.len() || column >= self.level.columns() {
+            let columns = self.level.columns().max(column + 1);
+            let layout = &mut self.level.layout;
+            layout.resize(layout.len().max(row + 1), Vec::new());
+            for cells in layout.iter_mut() {
+                cells.resize(columns, Cell::Empty);
+            }
+        }
+        self.level.layout[row][column] = self.brush;
+    }
+
+    pub fn erase(&mut self, row: usize, column: usize) {
+        if let Some(cell) = self
+            .level
+            .layout
+            .get_mut(row)
+            .and_then(|cells| cells.get_mut(column))
+        {
+            *cell = Cell::Empty;
+        }
+    }
+
+    pub fn ball_speed(&self, config: &GameConfig) -> f32 {
+        self.level.ball_speed.unwrap_or(config.ball_speed)
+    }
+
+    pub fn bar_width(&self, config: &GameConfig) -> f32 {
+        self.level.bar_width.unwrap_or(config.bar_width)
+    }
+
+    pub fn hearts(&self, config: &GameConfig) -> usize {
+        self.level.hearts.unwrap_or(config.initial_hearts)
+    }
+
+    pub fn adjust_ball_speed(&mut self, config: &GameConfig, delta: f32) {
+        let speed = (self.ball_speed(config) + delta).max(BALL_SPEED_STEP);
+        self.level.ball_speed = Some(speed);
+    }
+
+    pub fn adjust_bar_width(&mut self, config: &GameConfig, delta: f32) {
+        let width = (self.bar_width(config) + delta).clamp(BAR_WIDTH_STEP, config.width);
+        self.level.bar_width = Some(width);
+    }
+
+    pub fn adjust_hearts(&mut self, config: &GameConfig, delta: isize) {
+        let hearts = self.hearts(config).saturating_add_signed(delta).max(1);
+        self.level.hearts = Some(hearts);
+    }
+
+    // The level as it would be loaded from disk, or why it can't be played yet
+    pub fn playable(&self, config: &GameConfig) -> Result<Level, String> {
+        let mut level = self.level.clone();
+        level.layout.resize(self.rows(), Vec::new());
+        for cells in &mut level.layout {
+            cells.resize(self.columns(), Cell::Empty);
+        }
+        Level::parse(&level.to_file_string(), config).map_err(|e| e.message)
+    }
+
+    pub fn save(&self, config: &GameConfig) -> Result<(), String> {
+        let level = self.playable(config)?;
+        write_atomically(&self.path, level.to_file_string()).map_err(|e| e.to_string())
+    }
+}
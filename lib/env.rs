@@ -0,0 +1,132 @@
+// A gym-style wrapper around the headless simulation for training agents.
+// Kept in its own module rather than re-exported at the crate root, since its
+// Action is the agent's move and not a key binding.
+
+use crate::config::GameConfig;
+use crate::game::{GameState, Input, Player, SimEvent, Simulation};
+
+// Taken off the reward for every heart lost
+pub const HEART_LOST_PENALTY: f32 = 5.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Stay,
+    Left,
+    Right,
+}
+
+impl Action {
+    pub const ALL: [Action; 3] = [Action::Stay, Action::Left, Action::Right];
+
+    // For agents that pick actions by index
+    pub fn from_index(index: usize) -> Option<Action> {
+        Self::ALL.get(index).copied()
+    }
+
+    pub fn input(self) -> Input {
+        Input {
+            left: self == Action::Left,
+            right: self == Action::Right,
+            ..Input::default()
+        }
+    }
+}
+
+// What the agent sees after each step. The ball is the lowest one in play,
+// the next the bar has to deal with. With no ball in play, as once the game
+// is over, the ball fields are zero while the bar, hearts and score keep
+// their values.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Observation {
+    pub ball_x: f32,
+    pub ball_y: f32,
+    pub ball_dx: f32,
+    pub ball_dy: f32,
+    pub bar_x: f32,
+    pub bar_width: f32,
+    pub hearts: usize,
+    pub score: usize,
+}
+
+impl Observation {
+    pub const LEN: usize = 8;
+
+    pub fn from_sim(sim: &Simulation) -> Self {
+        let ball = sim.balls.iter().max_by(|a, b| a.y.total_cmp(&b.y));
+        Self {
+            ball_x: ball.map_or(0.0, |ball| ball.x),
+            ball_y: ball.map_or(0.0, |ball| ball.y),
+            ball_dx: ball.map_or(0.0, |ball| ball.dx),
+            ball_dy: ball.map_or(0.0, |ball| ball.dy),
+            bar_x: sim.bar.x,
+            bar_width: sim.bar.width,
+            hearts: sim.hearts,
+            score: sim.score,
+        }
+    }
+
+    // Scaled to roughly -1..1 by the field size and ball speed, for feeding
+    // straight into a network
+    pub fn to_array(&self, config: &GameConfig) -> [f32; Self::LEN] {
+        [
+            self.ball_x / config.width,
+            self.ball_y / config.height,
+            self.ball_dx / config.ball_speed,
+            self.ball_dy / config.ball_speed,
+            self.bar_x / config.width,
+            self.bar_width / config.width,
+            self.hearts as f32 / config.initial_hearts as f32,
+            self.score as f32 / 100.0,
+        ]
+    }
+}
+
+pub struct Env {
+    pub sim: Simulation,
+    pub ticks_per_step: u32, // the action is held for this many ticks
+}
+
+impl Env {
+    pub fn new(config: GameConfig) -> Self {
+        Self {
+            sim: Simulation::new(config, 0),
+            ticks_per_step: 1,
+        }
+    }
+
+    // Start a fresh game; the same seed always plays out the same way
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        let config = self.sim.config.clone();
+        self.sim = Simulation::new(config, seed);
+        Observation::from_sim(&self.sim)
+    }
+
+    // The reward is the points scored, as the game counts them, less
+    // HEART_LOST_PENALTY for each heart lost, the last one included. Stepping
+    // a finished game does nothing and keeps reporting done.
+    pub fn step(&mut self, action: Action) -> (Observation, f32, bool) {
+        let score = self.sim.score;
+        let input = action.input();
+        for _ in 0..self.ticks_per_step {
+            self.sim.step(input);
+        }
+        // Losing the last heart is reported as GameOver instead of HeartLost
+        let out_of_hearts = self.sim.hearts == 0;
+        let hearts_lost = self
+            .sim
+            .drain_events()
+            .iter()
+            .filter(|event| match event {
+                SimEvent::HeartLost { player, .. } => *player == Player::One,
+                SimEvent::GameOver => out_of_hearts,
+                _ => false,
+            })
+            .count();
+        let reward = (self.sim.score - score) as f32 - hearts_lost as f32 * HEART_LOST_PENALTY;
+        (
+            Observation::from_sim(&self.sim),
+            reward,
+            self.sim.state == GameState::GameOver,
+        )
+    }
+}
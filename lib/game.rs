@@ -1,36 +1,70 @@
+use crate::audio_settings::{AUDIO_SETTINGS_FILE, AudioSettings, Channel, VOLUME_STEP};
+use crate::autopilot::{Autopilot, Difficulty};
+use crate::bindings::{Action, KeyBindings, gamepad_action, key_name};
+use crate::bricks::{Brick, brick_grid};
+use crate::camera::{COMBO_SHAKE_MIN, COMBO_TRAUMA, Camera, GAME_OVER_TRAUMA, HEART_LOST_TRAUMA};
+use crate::config::{BAR_MARGIN, GameConfig};
+use crate::display_settings::{DISPLAY_SETTINGS_FILE, DisplaySettings};
+use crate::editor::{BALL_SPEED_STEP, BAR_WIDTH_STEP, EDITOR_FILE, Editor};
+use crate::high_scores::{HIGH_SCORES_FILE, HighScores};
+use crate::levels::{Cell, LEVELS_DIR, Level};
+use crate::menu::{ATTRACT_DELAY, Menu, MenuItem, MenuPage};
+use crate::net::{ClientSession, HostEvent, HostSession, NetError, Network, Snapshot};
+use crate::particles::{PARTICLE_SIZE, Particles};
+use crate::physics::{Aabb, Hit, paddle_bounce, reflect, sweep};
+use crate::power_ups::{
+    ActiveEffect, MAX_POWER_UPS, MULTI_BALL_EXTRA, MULTI_BALL_SPREAD, POWERUP_DURATION_TICKS,
+    POWERUP_SIZE, PowerUp, PowerUpType, SLOW_BALL_FACTOR, SPEED_BOOST_FACTOR,
+    WIDTH_INCREASE_FACTOR,
+};
+use crate::replay::{REPLAY_DIR, Replay, ReplayError, ReplayPlayer};
+use crate::sound::{Sound, SoundBank};
 use ggez::ContextBuilder;
 use ggez::conf::Conf;
-use ggez::graphics::{Drawable, Text, TextFragment};
-use ggez::input::keyboard::{KeyCode, KeyInput};
+use ggez::graphics::{Drawable, InstanceArray, Text, TextFragment};
+use ggez::input::gamepad::GamepadId;
+use ggez::input::gamepad::gilrs::{Axis, Button};
+use ggez::input::keyboard::{KeyCode, KeyInput, KeyboardContext};
+use ggez::input::mouse::MouseButton;
 use ggez::{
     Context, GameResult,
     conf::WindowMode,
     event::EventHandler,
     graphics::{self, Color, DrawParam},
 };
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
-pub const WIDTH: f32 = 1920.0;
-pub const HEIGHT: f32 = 1080.0;
-pub const BALL_SIZE: f32 = 20.0;
-pub const BAR_WIDTH: f32 = 150.0;
-pub const BAR_HEIGHT: f32 = 20.0;
-pub const BAR_SPEED: f32 = 10.0;
-pub const BALL_SPEED: f32 = 5.0;
-pub const INITIAL_HEARTS: usize = 3;
+pub const TICK_RATE: u32 = 60;
+pub const TICK_DT: f32 = 1.0 / TICK_RATE as f32;
+pub const MAX_TICKS_PER_FRAME: u32 = 8;
+pub const MAX_BOUNCES_PER_TICK: usize = 4;
+// Multi-ball never puts more than this many balls in play
+pub const MAX_BALLS: usize = 8;
+// Mixed into the run seed for the power-up random stream
+const POWER_UP_SEED: u64 = 0x5057_5255_5053;
+// Stick deflection below this is treated as centred
+pub const STICK_DEAD_ZONE: f32 = 0.2;
 
 pub struct Bar {
     pub x: f32,
     pub y: f32,
     pub width: f32,
+    pub height: f32,
+    pub speed: f32,
+    pub field_width: f32,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Ball {
     pub x: f32,
     pub y: f32,
     pub dx: f32,
     pub dy: f32,
+    pub size: f32,
 }
 
 pub struct AnimatedText {
@@ -65,310 +99,1742 @@ impl AnimatedText {
     }
 }
 
-#[derive(PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum GameState {
     Countdown,
     Playing,
     Paused,
     GameOver,
+    Editor,
+    MainMenu,
 }
 
-pub struct Game {
-    pub ball: Ball,
+// Turns variable frame times into a whole number of fixed simulation ticks
+pub struct FixedTimestep {
+    pub step: Duration,
+    pub accumulator: Duration,
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        Self::new(TICK_RATE)
+    }
+}
+
+impl FixedTimestep {
+    pub fn new(ticks_per_second: u32) -> Self {
+        Self {
+            step: Duration::from_secs(1) / ticks_per_second,
+            accumulator: Duration::ZERO,
+        }
+    }
+
+    // Returns how many ticks to run for `elapsed` time; after a long stall the
+    // backlog is dropped instead of trying to catch up all at once
+    pub fn advance(&mut self, elapsed: Duration) -> u32 {
+        self.accumulator += elapsed;
+        let mut ticks = 0;
+        while self.accumulator >= self.step {
+            self.accumulator -= self.step;
+            ticks += 1;
+        }
+        if ticks > MAX_TICKS_PER_FRAME {
+            self.accumulator = Duration::ZERO;
+            ticks = MAX_TICKS_PER_FRAME;
+        }
+        ticks
+    }
+}
+
+// Player input for a single simulation tick
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Input {
+    pub left: bool,
+    pub right: bool,
+    pub axis: i8,            // analog stick, -127 (full left) to 127 (full right)
+    pub target: Option<u16>, // mouse or touch x for the bar's centre to follow
+}
+
+impl Input {
+    pub fn from_keyboard(keyboard: &KeyboardContext, bindings: &KeyBindings) -> Self {
+        Self::from_player_keys(keyboard, bindings, Player::One)
+    }
+
+    pub fn from_player_keys(
+        keyboard: &KeyboardContext,
+        bindings: &KeyBindings,
+        player: Player,
+    ) -> Self {
+        let (left, right) = player.move_actions();
+        Self {
+            left: bindings.is_held(left, keyboard),
+            right: bindings.is_held(right, keyboard),
+            axis: 0,
+            target: None,
+        }
+    }
+
+    // Stick position in -1.0..=1.0, quantized so replays store it exactly
+    pub fn from_stick(value: f32) -> Self {
+        let value = if value.abs() < STICK_DEAD_ZONE {
+            0.0
+        } else {
+            value
+        };
+        Self {
+            axis: (value.clamp(-1.0, 1.0) * 127.0).round() as i8,
+            ..Self::default()
+        }
+    }
+
+    // Held buttons from either source; the first non-zero stick wins
+    pub fn merge(self, other: Input) -> Self {
+        Self {
+            left: self.left || other.left,
+            right: self.right || other.right,
+            axis: if self.axis != 0 {
+                self.axis
+            } else {
+                other.axis
+            },
+            target: self.target.or(other.target),
+        }
+    }
+
+    pub fn axis_amount(self) -> f32 {
+        self.axis as f32 / 127.0
+    }
+
+    pub fn is_steering(self) -> bool {
+        self.left || self.right || self.axis != 0
+    }
+
+    // One bit per button, as stored in replays
+    pub fn to_bits(self) -> u8 {
+        (self.left as u8) | (self.right as u8) << 1
+    }
+
+    pub fn from_bits(bits: u8) -> Self {
+        Self {
+            left: bits & 1 != 0,
+            right: bits & 1 << 1 != 0,
+            axis: 0,
+            target: None,
+        }
+    }
+}
+
+// Player one defends the bottom of the field; in two-player mode player two
+// defends the top
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Player {
+    One,
+    Two,
+}
+
+impl Player {
+    pub fn label(self) -> &'static str {
+        match self {
+            Player::One => "Player 1",
+            Player::Two => "Player 2",
+        }
+    }
+
+    pub fn move_actions(self) -> (Action, Action) {
+        match self {
+            Player::One => (Action::MoveLeft, Action::MoveRight),
+            Player::Two => (Action::PlayerTwoLeft, Action::PlayerTwoRight),
+        }
+    }
+
+    // Which bar the `order`th gamepad used steers in a two-player match. The
+    // keyboard keeps player one once their keys are used, so the first
+    // gamepad goes to player two.
+    pub fn for_gamepad(order: usize, keyboard_player_one: bool) -> Player {
+        match (order, keyboard_player_one) {
+            (0, true) | (1, false) => Player::Two,
+            _ => Player::One,
+        }
+    }
+}
+
+// The second player's bar, score and hearts in two-player mode
+pub struct PlayerTwo {
+    pub bar: Bar,
+    pub score: usize,
+    pub hearts: usize,
+}
+
+impl PlayerTwo {
+    pub fn new(config: &GameConfig) -> Self {
+        Self {
+            bar: Bar::new_top(config),
+            score: 0,
+            hearts: config.initial_hearts,
+        }
+    }
+}
+
+// What a ball bounced off, for effects
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Surface {
+    Bar,
+    Wall,
+    Brick,
+}
+
+impl Surface {
+    pub const ALL: [Surface; 3] = [Surface::Bar, Surface::Wall, Surface::Brick];
+}
+
+// Things that happened during a tick, for the presentation layer to react to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SimEvent {
+    BarHit,
+    NewHighScore(usize),
+    ExtraHeart,
+    HeartLost {
+        player: Player,
+        remaining: usize,
+    },
+    GameOver,
+    PowerUpCollected(PowerUpType),
+    PowerUpExpired(PowerUpType),
+    BrickHit,
+    BrickDestroyed {
+        score: usize,
+    },
+    StageCleared {
+        next_stage: usize,
+    },
+    // Where a ball touched something, and the way the surface faces
+    Impact {
+        x: f32,
+        y: f32,
+        normal: [f32; 2],
+        surface: Surface,
+    },
+    // A ball leaving the field at (x, y)
+    BallLost {
+        x: f32,
+        y: f32,
+    },
+}
+
+// What the ball ran into during a sweep
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Collider {
+    Wall,
+    Bar,
+    TopBar,
+    Brick(usize),
+}
+
+// Headless game rules: owns the balls, bar, score and hearts and never touches a ggez::Context
+pub struct Simulation {
+    pub config: GameConfig,
+    pub seed: u64,
+    pub rng: StdRng,
+    pub balls: Vec<Ball>, // only empty once the game is over
     pub bar: Bar,
     pub score: usize,
     pub high_score: usize,
     pub hearts: usize,
     pub state: GameState,
-    pub countdown_start: Option<Instant>,
-    pub countdown_value: i32,
-    pub fullscreen: bool,
-    pub animations: Vec<AnimatedText>,
     pub first_start: bool,
     pub prev_high_score: usize,
+    pub tracks_high_score: bool, // off for demo runs, which mustn't touch it
+    pub events: Vec<SimEvent>,
+    pub tick: u64,
+    pub power_ups: Vec<PowerUp>,
+    pub effects: Vec<ActiveEffect>,
+    // Separate stream so pickups don't change where the ball respawns
+    pub power_up_rng: StdRng,
+    pub bricks: Vec<Brick>,
+    pub obstacles: Vec<Aabb>,
+    pub stage: usize,
+    pub player_two: Option<PlayerTwo>, // only in two-player mode
 }
 
-impl Game {
-    pub fn new(_ctx: &mut Context) -> GameResult<Self> {
-        let mut game = Self {
-            ball: Ball::new(),
-            bar: Bar::new(),
+impl Simulation {
+    // The same seed and the same inputs always play out the same run
+    pub fn new(config: GameConfig, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut sim = Self {
+            seed,
+            balls: vec![Ball::new(&config, &mut rng)],
+            rng,
+            bar: Bar::new(&config),
             score: 0,
             high_score: 0,
-            hearts: INITIAL_HEARTS,
-            state: GameState::Countdown,
-            countdown_start: Some(Instant::now()),
-            countdown_value: 3,
-            fullscreen: false,
-            animations: Vec::new(),
+            hearts: config.initial_hearts,
+            state: GameState::Playing,
             first_start: true,
             prev_high_score: 0,
+            tracks_high_score: true,
+            events: Vec::new(),
+            tick: 0,
+            power_ups: Vec::new(),
+            effects: Vec::new(),
+            power_up_rng: StdRng::seed_from_u64(seed ^ POWER_UP_SEED),
+            bricks: Vec::new(),
+            obstacles: Vec::new(),
+            stage: 1,
+            player_two: config.two_player.then(|| PlayerTwo::new(&config)),
+            config,
         };
-        game.add_animation(
-            "Get Ready!".to_string(),
-            [WIDTH / 2.0, HEIGHT / 2.0],
-            2,
-            72.0,
-            Color::CYAN,
-        );
-        Ok(game)
+        sim.start_first_stage();
+        sim
     }
 
-    pub fn add_animation(
-        &mut self,
-        text: String,
-        position: [f32; 2],
-        duration_secs: u64,
-        scale: f32,
-        color: Color,
-    ) {
-        self.animations.push(AnimatedText::new(
-            text,
-            position,
-            duration_secs,
-            scale,
-            color,
-        ));
-    }
+    // Each run gets its own seed, drawn from the previous one so a session stays reproducible
     pub fn reset(&mut self) {
-        self.ball = Ball::new();
-        self.bar = Bar::new();
+        let seed = self.rng.r#gen();
+        self.reset_with_seed(seed);
+    }
+
+    pub fn reset_with_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+        self.balls = vec![Ball::new(&self.config, &mut self.rng)];
+        self.bar = Bar::new(&self.config);
         self.score = 0;
-        self.hearts = INITIAL_HEARTS;
-        self.state = GameState::Countdown;
-        self.countdown_start = Some(Instant::now());
-        self.countdown_value = 3;
+        self.hearts = self.config.initial_hearts;
+        self.state = GameState::Playing;
         self.first_start = false;
-        self.animations.clear(); // Clear all animations
-        self.add_animation(
-            "Get Ready!".to_string(),
-            [WIDTH / 2.0, HEIGHT / 2.0 - 50.0], // Adjusted position
-            1,
-            72.0,
-            Color::CYAN,
-        );
+        self.events.clear();
+        self.tick = 0;
+        self.power_ups.clear();
+        self.effects.clear();
+        self.power_up_rng = StdRng::seed_from_u64(seed ^ POWER_UP_SEED);
+        self.player_two = self.config.two_player.then(|| PlayerTwo::new(&self.config));
+        self.start_first_stage();
+    }
+
+    // The level being played, when levels are loaded; after the last one they
+    // start over. Two-player matches are played on an empty field.
+    pub fn current_level(&self) -> Option<&Level> {
+        let levels = &self.config.levels;
+        (!levels.is_empty() && !self.config.two_player)
+            .then(|| &levels[(self.stage - 1) % levels.len()])
+    }
+
+    pub fn has_stages(&self) -> bool {
+        !self.config.two_player && (self.config.brick_rows > 0 || !self.config.levels.is_empty())
+    }
+
+    fn start_first_stage(&mut self) {
+        self.stage = 1;
+        if let Some(hearts) = self.current_level().and_then(|level| level.hearts) {
+            self.hearts = hearts;
+        }
+        self.start_stage();
+        self.aim_serve(Player::One);
+    }
+
+    // Lay out the stage's bricks and obstacles and apply its ball speed and bar width
+    fn start_stage(&mut self) {
+        (self.bricks, self.obstacles) = match self.current_level() {
+            Some(level) => {
+                // Each time round all the levels bricks get one more hit point
+                let laps = (self.stage - 1) / self.config.levels.len();
+                (
+                    level.bricks(&self.config, laps as u32),
+                    level.obstacles(&self.config),
+                )
+            }
+            None if self.config.two_player => (Vec::new(), Vec::new()),
+            None => (brick_grid(&self.config, self.stage), Vec::new()),
+        };
+        self.apply_effects();
+        self.match_stage_ball_speed();
+    }
+
+    // Ball::new always uses the config speed; a level can ask for another
+    fn match_stage_ball_speed(&mut self) {
+        if let Some(speed) = self.current_level().and_then(|level| level.ball_speed) {
+            let scale = speed / self.config.ball_speed;
+            for ball in &mut self.balls {
+                ball.dx *= scale;
+                ball.dy *= scale;
+            }
+        }
+    }
+
+    // Advance the game by one TICK_DT; does nothing unless the game is being played
+    pub fn step(&mut self, input: Input) {
+        self.step_players(input, Input::default());
+    }
+
+    // `two` steers player two's bar and is ignored outside two-player mode
+    pub fn step_players(&mut self, one: Input, two: Input) {
+        if self.state != GameState::Playing {
+            return;
+        }
+        self.tick += 1;
+        self.handle_ball_collisions(TICK_DT * self.ball_time_scale());
+        self.collect_power_ups();
+        self.expire_effects();
+        self.handle_bar_movement(one);
+        if let Some(player_two) = &mut self.player_two {
+            player_two.bar.steer(two, &self.config);
+        }
+        self.separate_balls_from_bar();
     }
 
+    // In two-player mode, whoever still has hearts once the game is over
+    pub fn winner(&self) -> Option<Player> {
+        let player_two = self.player_two.as_ref()?;
+        (self.state == GameState::GameOver).then_some(if player_two.hearts == 0 {
+            Player::One
+        } else {
+            Player::Two
+        })
+    }
+
+    pub fn drain_events(&mut self) -> Vec<SimEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    // A two-player match is scored between the players, not against the table
     pub fn check_high_score(&mut self) {
+        if self.player_two.is_some() || !self.tracks_high_score {
+            return;
+        }
         if self.score > self.high_score {
-            self.animations.clear(); // Clear previous animations
             if self.high_score > 0 {
-                self.add_animation(
-                    format!("New High Score: {}!", self.score),
-                    [WIDTH / 2.0, HEIGHT / 2.0 - 70.0], // Adjusted position
-                    2,
-                    48.0,
-                    Color::CYAN,
-                );
+                self.events.push(SimEvent::NewHighScore(self.score));
             }
             self.high_score = self.score;
 
             if !self.first_start && self.score > self.prev_high_score + 5 {
                 self.hearts += 1;
-                self.add_animation(
-                    "Extra Heart Awarded!".to_string(),
-                    [WIDTH / 2.0, HEIGHT / 2.0 - 120.0], // Adjusted position
-                    2,
-                    36.0,
-                    Color::GREEN,
-                );
+                self.events.push(SimEvent::ExtraHeart);
                 self.prev_high_score = self.score;
             }
         }
     }
 
-    pub fn handle_ball_collisions(&mut self) {
-        // Ball-wall collision
-        if self.ball.x <= 0.0 || self.ball.x + BALL_SIZE >= WIDTH {
-            self.ball.dx *= -1.0;
-        }
-        if self.ball.y <= 0.0 {
-            self.ball.dy *= -1.0;
+    // Side and top walls, thick enough that nothing can skip past them in one
+    // tick, then the bar and any bricks. Player two's bar takes the place of
+    // the top wall.
+    fn colliders(&self) -> Vec<(Collider, Aabb)> {
+        let (width, height) = (self.config.width, self.config.height);
+        let t = height;
+        let top = match &self.player_two {
+            Some(player_two) => (Collider::TopBar, player_two.bar.aabb()),
+            None => (Collider::Wall, Aabb::new(-t, -t, width + 2.0 * t, t)),
+        };
+        let mut colliders = vec![
+            (Collider::Wall, Aabb::new(-t, -t, t, height + 2.0 * t)),
+            (Collider::Wall, Aabb::new(width, -t, t, height + 2.0 * t)),
+            top,
+            (Collider::Bar, self.bar.aabb()),
+        ];
+        colliders.extend(
+            self.obstacles
+                .iter()
+                .map(|&obstacle| (Collider::Wall, obstacle)),
+        );
+        colliders.extend(
+            self.bricks
+                .iter()
+                .enumerate()
+                .map(|(i, brick)| (Collider::Brick(i), brick.aabb())),
+        );
+        colliders
+    }
+
+    // Move every ball for `dt` seconds. A heart is only lost once the last
+    // ball has gone, by the player whose edge it went past.
+    pub fn handle_ball_collisions(&mut self, dt: f32) {
+        for index in 0..self.balls.len() {
+            self.move_ball(index, dt);
         }
+        self.keep_balls_in_field();
 
-        // Ball-bar collision
-        if self.ball.y + BALL_SIZE >= self.bar.y
-            && self.ball.x + BALL_SIZE >= self.bar.x
-            && self.ball.x <= self.bar.x + self.bar.width
-        {
-            self.ball.dy *= -1.0;
-            self.score += 1;
-            self.check_high_score();
+        if self.bricks.is_empty() && self.has_stages() {
+            self.advance_stage();
         }
 
-        // Ball falls off screen
-        if self.ball.y > HEIGHT {
-            self.hearts -= 1;
-            self.animations.clear(); // Clear existing animations
-            if self.hearts == 0 {
+        // Balls fall off screen, or past player two off the top
+        let height = self.config.height;
+        let mut loser = Player::One;
+        let events = &mut self.events;
+        self.balls.retain(|ball| {
+            if ball.y + ball.size < 0.0 {
+                loser = Player::Two;
+            }
+            let in_field = ball.y <= height && ball.y + ball.size >= 0.0;
+            if !in_field {
+                events.push(SimEvent::BallLost {
+                    x: ball.x + ball.size / 2.0,
+                    y: ball.y.clamp(0.0, height),
+                });
+            }
+            in_field
+        });
+        if self.balls.is_empty() {
+            let hearts = match (loser, &mut self.player_two) {
+                (Player::Two, Some(player_two)) => &mut player_two.hearts,
+                _ => &mut self.hearts,
+            };
+            *hearts -= 1;
+            let remaining = *hearts;
+            if remaining == 0 {
                 self.state = GameState::GameOver;
-                self.add_animation(
-                    "Game Over!".to_string(),
-                    [WIDTH / 2.0, HEIGHT / 2.0 - 50.0], // Adjusted position
-                    999,
-                    72.0,
-                    Color::RED,
+                self.events.push(SimEvent::GameOver);
+            } else {
+                self.events.push(SimEvent::HeartLost {
+                    player: loser,
+                    remaining,
+                });
+                self.serve_ball();
+                self.aim_serve(loser);
+            }
+        }
+    }
+
+    // Bounce one ball at the exact time of impact so fast balls can't
+    // tunnel through the bar or a wall
+    fn move_ball(&mut self, index: usize, dt: f32) {
+        let mut remaining = dt;
+        for _ in 0..MAX_BOUNCES_PER_TICK {
+            let ball = self.balls[index];
+            let delta = [ball.dx * remaining, ball.dy * remaining];
+            let mut first: Option<(Hit, Collider)> = None;
+            for (collider, target) in self.colliders() {
+                if let Some(hit) = sweep(ball.aabb(), delta, target)
+                    && first.is_none_or(|(best, _)| hit.time < best.time)
+                {
+                    first = Some((hit, collider));
+                }
+            }
+
+            let Some((hit, collider)) = first else {
+                self.balls[index].update(remaining);
+                break;
+            };
+            self.balls[index].update(remaining * hit.time);
+            remaining *= 1.0 - hit.time;
+            let ball = &mut self.balls[index];
+            let half = ball.size / 2.0;
+            self.events.push(SimEvent::Impact {
+                x: ball.x + half - hit.normal[0] * half,
+                y: ball.y + half - hit.normal[1] * half,
+                normal: hit.normal,
+                surface: match collider {
+                    Collider::Bar | Collider::TopBar => Surface::Bar,
+                    Collider::Wall => Surface::Wall,
+                    Collider::Brick(_) => Surface::Brick,
+                },
+            });
+            // Only landing on top of the bar counts, the sides just deflect
+            if collider == Collider::Bar && hit.normal == [0.0, -1.0] {
+                [ball.dx, ball.dy] = paddle_bounce(
+                    [ball.dx, ball.dy],
+                    self.bar.hit_offset(ball),
+                    self.config.max_bounce_angle_radians(),
+                    self.config.min_vertical_speed,
                 );
-                self.add_animation(
-                    "Press 'R' to retry".to_string(),
-                    [WIDTH / 2.0, HEIGHT / 2.0 + 50.0], // Adjusted position
-                    999,
-                    36.0,
-                    Color::WHITE,
+                self.score += 1;
+                self.events.push(SimEvent::BarHit);
+                self.check_high_score();
+                self.spawn_power_up();
+            } else if collider == Collider::TopBar
+                && hit.normal == [0.0, 1.0]
+                && let Some(player_two) = &mut self.player_two
+            {
+                // The same bounce as the bottom bar, mirrored to head down
+                let [dx, dy] = paddle_bounce(
+                    [ball.dx, ball.dy],
+                    player_two.bar.hit_offset(ball),
+                    self.config.max_bounce_angle_radians(),
+                    self.config.min_vertical_speed,
                 );
+                [ball.dx, ball.dy] = [dx, -dy];
+                player_two.score += 1;
+                self.events.push(SimEvent::BarHit);
             } else {
-                self.add_animation(
-                    format!("Lost a heart! {} remaining", self.hearts),
-                    [WIDTH / 2.0, HEIGHT / 2.0], // Adjusted position
-                    2,
-                    48.0,
-                    Color::RED,
-                );
-                self.ball = Ball::new();
+                [ball.dx, ball.dy] = reflect([ball.dx, ball.dy], hit.normal);
+                if let Collider::Brick(brick) = collider {
+                    self.hit_brick(brick);
+                }
             }
         }
     }
 
-    pub fn handle_bar_movement(&mut self, ctx: &mut Context) {
-        let keys = ctx.keyboard.pressed_keys();
-        if keys.contains(&KeyCode::Left) || keys.contains(&KeyCode::A) {
-            self.bar.move_left();
+    // A single fresh ball, at the stage's speed
+    fn serve_ball(&mut self) {
+        self.balls = vec![Ball::new(&self.config, &mut self.rng)];
+        self.match_stage_ball_speed();
+    }
+
+    // Two-player serves start from the middle, heading for the player who
+    // lost the last point
+    fn aim_serve(&mut self, toward: Player) {
+        if self.player_two.is_none() {
+            return;
         }
-        if keys.contains(&KeyCode::Right) || keys.contains(&KeyCode::D) {
-            self.bar.move_right();
+        let height = self.config.height;
+        for ball in &mut self.balls {
+            ball.y = (height - ball.size) / 2.0;
+            ball.dy = match toward {
+                Player::One => ball.dy.abs(),
+                Player::Two => -ball.dy.abs(),
+            };
         }
     }
-}
-
-impl EventHandler for Game {
-    fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
-        self.animations.retain(|anim| anim.is_active()); // Retain only active animations
 
-        match self.state {
-            GameState::GameOver => return Ok(()),
-            GameState::Paused => return Ok(()),
-            GameState::Countdown => {
-                if let Some(start_time) = self.countdown_start {
-                    if Instant::now().duration_since(start_time) >= Duration::from_secs(1) {
-                        self.countdown_value -= 1;
-                        self.countdown_start = Some(Instant::now());
-                        // Add countdown animation without clearing existing ones
-                        if self.countdown_value > 0 {
-                            self.animations.clear(); // Clear only before adding "Game Start!" ??
-
-                            self.add_animation(
-                                format!("{}", self.countdown_value),
-                                [WIDTH / 2.0, HEIGHT / 2.0 - 100.0], // Adjusted position
-                                1,
-                                96.0,
-                                Color::CYAN,
-                            );
-                            self.animations.clear(); // Clear only before adding "Game Start!" ??
-                        } else {
-                            self.state = GameState::Playing;
-                            self.animations.clear(); // Clear only before adding "Game Start!"
-                            self.add_animation(
-                                "Game Start!".to_string(),
-                                [WIDTH / 2.0, HEIGHT / 2.0 - 50.0], // Adjusted position
-                                2,
-                                72.0,
-                                Color::GREEN,
-                            );
-                            self.add_animation(
-                                "Press 'P' or SPACE to pause".to_string(),
-                                [WIDTH / 2.0, HEIGHT / 2.0 + 50.0], // Adjusted position
-                                3,
-                                24.0,
-                                Color::WHITE,
-                            );
-                        }
-                    }
-                }
-                return Ok(());
-            }
-            GameState::Playing => {
-                self.ball.update();
-                self.handle_ball_collisions(); // Handle ball collisions
-                self.handle_bar_movement(ctx); // Handle bar movement
+    // Extra balls split off the first one, fanning out either side of its path
+    pub fn spawn_extra_balls(&mut self, count: usize) {
+        let Some(&ball) = self.balls.first() else {
+            return;
+        };
+        for i in 0..count {
+            if self.balls.len() >= MAX_BALLS {
+                break;
             }
+            let side = if i % 2 == 0 { 1.0 } else { -1.0 };
+            let angle = side * MULTI_BALL_SPREAD * (i / 2 + 1) as f32;
+            let (sin, cos) = angle.sin_cos();
+            let mut extra = ball;
+            extra.dx = ball.dx * cos - ball.dy * sin;
+            extra.dy = ball.dx * sin + ball.dy * cos;
+            self.balls.push(extra);
         }
+    }
 
-        Ok(())
+    fn hit_brick(&mut self, index: usize) {
+        if !self.bricks[index].hit() {
+            self.events.push(SimEvent::BrickHit);
+            return;
+        }
+        let brick = self.bricks.remove(index);
+        self.score += brick.score;
+        self.events
+            .push(SimEvent::BrickDestroyed { score: brick.score });
+        self.check_high_score();
     }
-    fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
-        // Clear the screen with a black background to remove previous frames
-        let mut canvas = graphics::Canvas::from_frame(ctx, Color::BLACK);
 
-        // Draw score and lives (top left)
-        let score_text = Text::new(
-            TextFragment::new(format!(
-                "Score: {}\nHearts: {}\nHigh Score: {}",
-                self.score, self.hearts, self.high_score
-            ))
-            .scale(24.0),
-        );
-        score_text.draw(&mut canvas, DrawParam::default().dest([20.0, 20.0]));
+    // A cleared grid is replaced by the next level, or a tougher grid, and
+    // the ball starts over. A level's hearts top the player up to that many.
+    pub fn advance_stage(&mut self) {
+        self.stage += 1;
+        self.balls = vec![Ball::new(&self.config, &mut self.rng)];
+        if let Some(hearts) = self.current_level().and_then(|level| level.hearts) {
+            self.hearts = self.hearts.max(hearts);
+        }
+        self.start_stage();
+        self.events.push(SimEvent::StageCleared {
+            next_stage: self.stage,
+        });
+    }
 
-        // Draw controls (top right)
-        let controls_text = Text::new(
-            TextFragment::new("Controls:\nF - Fullscreen\nP/Space - Pause\nR - Retry").scale(20.0),
-        );
-        controls_text.draw(
-            &mut canvas,
-            DrawParam::default().dest([WIDTH - 200.0, 20.0]),
-        );
+    // Position correction for float drift at the walls; with two players
+    // there is no top wall to keep the ball in
+    fn keep_balls_in_field(&mut self) {
+        let top_wall = self.player_two.is_none();
+        for ball in &mut self.balls {
+            ball.x = ball.x.clamp(0.0, self.config.width - ball.size);
+            if top_wall {
+                ball.y = ball.y.max(0.0);
+            }
+        }
+    }
 
-        // Draw countdown or game elements
-        if self.state == GameState::Countdown {
-            let countdown_text = Text::new(
-                TextFragment::new(format!("{}", self.countdown_value))
-                    .scale(96.0)
-                    .color(Color::CYAN),
-            );
-            let dims = countdown_text.dimensions(ctx).unwrap();
-            countdown_text.draw(
-                &mut canvas,
-                DrawParam::default()
-                    .dest([WIDTH / 2.0 - dims.w / 2.0, HEIGHT / 2.0 - dims.h / 2.0]),
-            );
-        } else {
-            // Draw game objects
-            let ball_rect = graphics::Rect::new(self.ball.x, self.ball.y, BALL_SIZE, BALL_SIZE);
-            let ball = graphics::Mesh::new_rectangle(
-                ctx,
-                graphics::DrawMode::fill(),
-                ball_rect,
-                Color::WHITE,
-            )?;
-            ball.draw(&mut canvas, DrawParam::default());
+    // A bar can slide into a ball from the side; push the ball back out
+    // instead of letting it stick inside
+    pub fn separate_balls_from_bar(&mut self) {
+        let mut bars = vec![self.bar.aabb()];
+        bars.extend(
+            self.player_two
+                .as_ref()
+                .map(|player_two| player_two.bar.aabb()),
+        );
+        for ball in &mut self.balls {
+            for bar in &bars {
+                let aabb = ball.aabb();
+                if !aabb.overlaps(bar) {
+                    continue;
+                }
+                if aabb.center()[0] < bar.center()[0] {
+                    ball.x = bar.x - ball.size;
+                    ball.dx = -ball.dx.abs();
+                } else {
+                    ball.x = bar.x + bar.w;
+                    ball.dx = ball.dx.abs();
+                }
+            }
+        }
+        self.keep_balls_in_field();
+    }
 
-            let bar_rect = graphics::Rect::new(self.bar.x, self.bar.y, self.bar.width, BAR_HEIGHT);
-            let bar = graphics::Mesh::new_rectangle(
-                ctx,
-                graphics::DrawMode::fill(),
-                bar_rect,
-                Color::GREEN,
-            )?;
-            bar.draw(&mut canvas, DrawParam::default());
+    // Each time the ball lands on the bar there is a chance of a pickup
+    // appearing somewhere in the top half of the field, if it is big enough
+    // to hold one. They would only help player one, so two-player matches go
+    // without.
+    pub fn spawn_power_up(&mut self) {
+        let max_x = self.config.width - POWERUP_SIZE;
+        let max_y = self.config.height / 2.0 - POWERUP_SIZE;
+        if self.player_two.is_some()
+            || self.power_ups.len() >= MAX_POWER_UPS
+            || max_x <= 0.0
+            || max_y <= 0.0
+            || self.power_up_rng.r#gen::<f32>() >= self.config.power_up_chance
+        {
+            return;
         }
+        let index = self.power_up_rng.gen_range(0..PowerUpType::ALL.len());
+        let x = self.power_up_rng.gen_range(0.0..max_x);
+        let y = self.power_up_rng.gen_range(0.0..max_y);
+        self.power_ups
+            .push(PowerUp::new(x, y, PowerUpType::ALL[index]));
+    }
 
-        // Draw animations
-        for anim in &self.animations {
-            let text = Text::new(
-                TextFragment::new(&anim.text)
-                    .scale(anim.scale)
-                    .color(anim.color),
+    // Pickups any ball touches are removed from the field and take effect
+    pub fn collect_power_ups(&mut self) {
+        let balls: Vec<Aabb> = self.balls.iter().map(Ball::aabb).collect();
+        let (collected, remaining): (Vec<PowerUp>, Vec<PowerUp>) = self
+            .power_ups
+            .iter()
+            .partition(|power_up| balls.iter().any(|ball| power_up.aabb().overlaps(ball)));
+        if collected.is_empty() {
+            return;
+        }
+        self.power_ups = remaining;
+        for power_up in collected {
+            let power_type = power_up.power_type;
+            if power_type.is_timed() {
+                // Collecting one that is already running restarts its timer
+                // instead of stacking
+                self.effects
+                    .retain(|effect| effect.power_type != power_type);
+                self.effects.push(ActiveEffect {
+                    power_type,
+                    until_tick: self.tick + POWERUP_DURATION_TICKS,
+                });
+            } else if power_type == PowerUpType::ExtraHeart {
+                self.hearts += 1;
+            } else {
+                self.spawn_extra_balls(MULTI_BALL_EXTRA);
+            }
+            self.events.push(SimEvent::PowerUpCollected(power_type));
+        }
+        self.apply_effects();
+    }
+
+    pub fn expire_effects(&mut self) {
+        let tick = self.tick;
+        let before = self.effects.len();
+        for effect in self
+            .effects
+            .iter()
+            .filter(|effect| effect.until_tick <= tick)
+        {
+            self.events
+                .push(SimEvent::PowerUpExpired(effect.power_type));
+        }
+        self.effects.retain(|effect| effect.until_tick > tick);
+        if self.effects.len() != before {
+            self.apply_effects();
+        }
+    }
+
+    pub fn has_effect(&self, power_type: PowerUpType) -> bool {
+        self.effects
+            .iter()
+            .any(|effect| effect.power_type == power_type)
+    }
+
+    // Ticks until an active effect wears off
+    pub fn effect_ticks_left(&self, power_type: PowerUpType) -> Option<u64> {
+        self.effects
+            .iter()
+            .find(|effect| effect.power_type == power_type)
+            .map(|effect| effect.until_tick.saturating_sub(self.tick))
+    }
+
+    // Bar size and speed are always derived from the config and the effects
+    // running now, so an effect running out restores them exactly
+    fn apply_effects(&mut self) {
+        let mut width = self
+            .current_level()
+            .and_then(|level| level.bar_width)
+            .unwrap_or(self.config.bar_width);
+        let mut speed = self.config.bar_speed;
+        if self.has_effect(PowerUpType::WidthIncrease) {
+            width *= WIDTH_INCREASE_FACTOR;
+        }
+        if self.has_effect(PowerUpType::SpeedBoost) {
+            speed *= SPEED_BOOST_FACTOR;
+        }
+        self.bar.set_width(width);
+        self.bar.speed = speed;
+    }
+
+    // Slow Ball runs the ball's clock slower rather than touching its velocity
+    pub fn ball_time_scale(&self) -> f32 {
+        if self.has_effect(PowerUpType::SlowBall) {
+            SLOW_BALL_FACTOR
+        } else {
+            1.0
+        }
+    }
+
+    pub fn handle_bar_movement(&mut self, input: Input) {
+        self.bar.steer(input, &self.config);
+    }
+}
+
+pub struct Game {
+    pub sim: Simulation,
+    pub countdown_start: Option<Instant>,
+    pub countdown_value: i32,
+    pub fullscreen: bool,
+    pub animations: Vec<AnimatedText>,
+    pub timestep: FixedTimestep,
+    pub high_scores: HighScores,
+    pub high_scores_path: PathBuf,
+    pub recording: Replay,
+    pub playback: Option<ReplayPlayer>,
+    pub taken_over: bool, // the run carries on from the end of a replay
+    pub replay_dir: PathBuf,
+    pub bindings: KeyBindings,
+    pub rebinding: Option<Action>,
+    pub pad: Input,     // held d-pad buttons and stick, updated by gamepad events
+    pub pad_two: Input, // the same for the second gamepad in two-player mode
+    pub gamepads: Vec<GamepadId>, // in the order they were first used
+    pub keyboard_player_one: bool, // player one's keys used in a local two-player match
+    pub pointer: Option<u16>, // last mouse or touch x, until keys take over again
+    pub sounds: SoundBank,
+    pub audio_settings: AudioSettings,
+    pub audio_settings_path: PathBuf,
+    pub display_settings: DisplaySettings,
+    pub display_settings_path: PathBuf,
+    pub first_seed: Option<u64>, // the launch seed, kept for the session's first run
+    pub editor: Option<Editor>,  // kept after leaving so unsaved edits survive
+    pub campaign: Option<Vec<Level>>, // the real levels, put aside while play-testing
+    pub net: Option<Network>,
+    pub autopilot: Option<Autopilot>, // plays the bottom bar in demo mode
+    pub two_player_after_demo: bool,  // the mode the menu had chosen before the demo
+    pub particles: Particles,
+    pub particle_batch: Option<InstanceArray>, // made on the first draw, then reused for all particles
+    pub camera: Camera,
+    pub combo: usize, // bricks broken since the ball last touched a bar
+    pub menu: Menu,
+    pub menu_idle_since: Instant, // for starting the demo on an untouched title screen
+}
+
+impl Game {
+    pub fn new(ctx: &mut Context, config: GameConfig, seed: u64) -> GameResult<Self> {
+        let mut game = Self::headless(config, seed, ctx.fs.user_data_dir());
+        game.sounds = SoundBank::load();
+        game.sounds.apply_settings(&game.audio_settings);
+        game.sounds.start_music();
+        Ok(game)
+    }
+
+    // Everything but the window and sound, with the files that would go in
+    // the user data directory kept in `data_dir`
+    pub fn headless(config: GameConfig, seed: u64, data_dir: &Path) -> Self {
+        let high_scores_path = data_dir.join(HIGH_SCORES_FILE);
+        let high_scores = HighScores::load(&high_scores_path);
+        let bindings = config.bindings.clone();
+        let audio_settings_path = data_dir.join(AUDIO_SETTINGS_FILE);
+        let audio_settings = AudioSettings::load(&audio_settings_path);
+        let display_settings_path = data_dir.join(DISPLAY_SETTINGS_FILE);
+        let display_settings = DisplaySettings::load(&display_settings_path);
+        let mut sim = Simulation::new(config, seed);
+        sim.high_score = high_scores.best();
+        sim.prev_high_score = sim.high_score;
+        let mut game = Self {
+            bindings,
+            rebinding: None,
+            pad: Input::default(),
+            pad_two: Input::default(),
+            gamepads: Vec::new(),
+            keyboard_player_one: false,
+            pointer: None,
+            sounds: SoundBank::default(),
+            audio_settings,
+            audio_settings_path,
+            display_settings,
+            display_settings_path,
+            first_seed: Some(seed),
+            editor: None,
+            campaign: None,
+            net: None,
+            autopilot: None,
+            two_player_after_demo: false,
+            particles: Particles::default(),
+            particle_batch: None,
+            camera: Camera::new(sim.config.screen_shake && display_settings.screen_shake),
+            combo: 0,
+            menu: Menu::default(),
+            menu_idle_since: Instant::now(),
+            recording: Replay::start(&sim),
+            playback: None,
+            taken_over: false,
+            replay_dir: data_dir.join(REPLAY_DIR),
+            sim,
+            countdown_start: Some(Instant::now()),
+            countdown_value: 3,
+            fullscreen: false,
+            animations: Vec::new(),
+            timestep: FixedTimestep::default(),
+            high_scores,
+            high_scores_path,
+        };
+        game.open_menu();
+        game
+    }
+
+    pub fn screen_center(&self) -> [f32; 2] {
+        [self.sim.config.width / 2.0, self.sim.config.height / 2.0]
+    }
+
+    pub fn add_animation(
+        &mut self,
+        text: String,
+        position: [f32; 2],
+        duration_secs: u64,
+        scale: f32,
+        color: Color,
+    ) {
+        self.animations.push(AnimatedText::new(
+            text,
+            position,
+            duration_secs,
+            scale,
+            color,
+        ));
+    }
+    pub fn reset(&mut self) {
+        self.save_unfinished_replay();
+        match &mut self.playback {
+            Some(player) => {
+                player.tick = 0;
+                player.replay.restore(&mut self.sim);
+            }
+            // The menu comes first, so the first run played still gets the
+            // launch seed and the first-start rules
+            None => match self.first_seed.take_if(|_| self.autopilot.is_none()) {
+                Some(seed) => {
+                    self.sim.reset_with_seed(seed);
+                    self.sim.first_start = true;
+                }
+                None => self.sim.reset(),
+            },
+        }
+        // A run carried on from a replay had the replay's high score
+        if std::mem::take(&mut self.taken_over) {
+            self.restore_high_score();
+        }
+        self.recording = Replay::start(&self.sim);
+        self.sim.state = GameState::Countdown;
+        self.countdown_start = Some(Instant::now());
+        self.countdown_value = 3;
+        self.timestep.accumulator = Duration::ZERO;
+        self.pointer = None;
+        self.particles.clear();
+        self.camera.settle();
+        self.combo = 0;
+        self.animations.clear(); // Clear all animations
+        let [cx, cy] = self.screen_center();
+        self.add_animation(
+            "Get Ready!".to_string(),
+            [cx, cy - 50.0], // Adjusted position
+            1,
+            72.0,
+            Color::CYAN,
+        );
+    }
+
+    // Switch to feeding a recorded run through the game loop instead of the keyboard
+    pub fn play_replay(&mut self, replay: Replay) -> Result<(), ReplayError> {
+        replay.check_config(&self.sim.config)?;
+        self.playback = Some(ReplayPlayer::new(replay));
+        self.reset();
+        Ok(())
+    }
+
+    // Both players' input for the next tick: from the replay being played back, or live
+    fn next_inputs(&mut self, live: (Input, Input)) -> Option<(Input, Input)> {
+        let Some(player) = &mut self.playback else {
+            return Some(live);
+        };
+        let inputs = player.next_inputs();
+        if inputs.is_none() {
+            // Hand control to the player from where the recording stops
+            self.playback = None;
+            self.taken_over = true;
+            self.sim.state = GameState::Paused;
+            let [cx, cy] = self.screen_center();
+            self.add_animation(
+                format!(
+                    "Replay finished, press {} to take over",
+                    self.bindings.describe(Action::Pause)
+                ),
+                [cx, cy],
+                999,
+                48.0,
+                Color::CYAN,
+            );
+        }
+        inputs
+    }
+
+    // What the players are holding this frame. Player one steers with their
+    // keys, gamepad or pointer; player two with their own keys and gamepad, or
+    // from the other machine when hosting.
+    fn live_inputs(&mut self, ctx: &Context) -> (Input, Input) {
+        let keys = Input::from_keyboard(&ctx.keyboard, &self.bindings);
+        if keys.is_steering()
+            && !self.keyboard_player_one
+            && self.sim.player_two.is_some()
+            && self.net.is_none()
+        {
+            // The gamepads change bars, so drop what they were holding
+            self.keyboard_player_one = true;
+            self.pad = Input::default();
+            self.pad_two = Input::default();
+        }
+        let mut live = keys.merge(self.pad);
+        // Keys or the stick take the bar back until the pointer moves again
+        if live.is_steering() {
+            self.pointer = None;
+        }
+        live.target = self.pointer;
+        let live_two = match &self.net {
+            Some(Network::Host(host)) => host.remote_input(),
+            _ => Input::from_player_keys(&ctx.keyboard, &self.bindings, Player::Two)
+                .merge(self.pad_two),
+        };
+        (live, live_two)
+    }
+
+    // Steer toward a mouse or touch position in the window, which the bar
+    // only follows during play. Through the camera's view it lands on the
+    // field where it is drawn.
+    fn point_at(&mut self, ctx: &Context, x: f32, y: f32) {
+        if self.sim.state != GameState::Playing {
+            return;
+        }
+        let (width, height) = ctx.gfx.drawable_size();
+        let screen = graphics::Rect::new(0.0, 0.0, width, height);
+        let [x, _] = self.camera.view_point(screen, [x, y]);
+        self.pointer = Some(x.clamp(0.0, u16::MAX as f32) as u16);
+    }
+
+    // Run as many fixed ticks as `elapsed` covers, recording the inputs used
+    pub fn advance(&mut self, elapsed: Duration, live: (Input, Input)) {
+        for _ in 0..self.timestep.advance(elapsed) {
+            if self.sim.state != GameState::Playing {
+                break;
+            }
+            let Some((mut input, input_two)) = self.next_inputs(live) else {
+                break;
+            };
+            if let Some(autopilot) = &mut self.autopilot {
+                input = autopilot.next_input(&self.sim);
+            }
+            self.recording.record(input);
+            if self.sim.player_two.is_some() {
+                self.recording.record_player_two(input_two);
+            }
+            self.sim.step_players(input, input_two);
+        }
+    }
+
+    // Accept player two from another machine and run the match for both;
+    // returns the address actually listened on
+    pub fn host(&mut self, addr: impl ToSocketAddrs) -> Result<SocketAddr, NetError> {
+        let host = HostSession::bind(addr, &self.sim.config)?;
+        let addr = host.local_addr()?;
+        self.net = Some(Network::Host(host));
+        self.sim.state = GameState::Paused;
+        self.animations.clear();
+        let [cx, cy] = self.screen_center();
+        self.add_animation(
+            format!("Waiting for Player 2 on {}", addr),
+            [cx, cy],
+            999,
+            48.0,
+            Color::CYAN,
+        );
+        Ok(addr)
+    }
+
+    // Play as player two in a match hosted on another machine
+    pub fn join(&mut self, addr: impl ToSocketAddrs) -> Result<(), NetError> {
+        let client = ClientSession::connect(addr, &self.sim.config)?;
+        self.net = Some(Network::Client(Box::new(client)));
+        self.sim.state = GameState::Paused;
+        self.animations.clear();
+        let [cx, cy] = self.screen_center();
+        self.add_animation(
+            "Connecting to host...".to_string(),
+            [cx, cy],
+            999,
+            48.0,
+            Color::CYAN,
+        );
+        Ok(())
+    }
+
+    // Let the autopilot play on its own until a key is pressed. It only plays
+    // the bottom bar, so the demo is always a one-player run, whatever mode
+    // the menu last chose.
+    pub fn start_demo(&mut self, difficulty: Difficulty) {
+        self.autopilot = Some(Autopilot::new(difficulty, self.sim.seed));
+        self.two_player_after_demo = self.sim.config.two_player;
+        self.switch_mode(false);
+        self.sim.tracks_high_score = false;
+        self.reset();
+    }
+
+    // Back to the title screen the demo was standing in for
+    pub fn stop_demo(&mut self) {
+        self.switch_mode(self.two_player_after_demo);
+        // Still the demo's run, so leaving it saves no replay
+        self.open_menu();
+        self.autopilot = None;
+        self.sim.tracks_high_score = true;
+        self.restore_high_score();
+    }
+
+    // The best recorded score, whatever the last run did to the one on screen
+    fn restore_high_score(&mut self) {
+        self.sim.high_score = self.high_scores.best();
+        self.sim.prev_high_score = self.sim.high_score;
+    }
+
+    pub fn is_client(&self) -> bool {
+        matches!(self.net, Some(Network::Client(_) | Network::Disconnected))
+    }
+
+    // A host has nobody to play against until player two joins
+    pub fn waiting_for_peer(&self) -> bool {
+        matches!(&self.net, Some(Network::Host(host)) if !host.is_connected())
+    }
+
+    fn poll_host(&mut self) {
+        let Some(Network::Host(host)) = &mut self.net else {
+            return;
+        };
+        for event in host.poll() {
+            self.host_event(event);
+        }
+    }
+
+    fn host_event(&mut self, event: HostEvent) {
+        match event {
+            HostEvent::Joined => self.reset(),
+            HostEvent::Left(reason) => {
+                if self.sim.state != GameState::GameOver {
+                    self.sim.state = GameState::Paused;
+                }
+                self.animations.clear();
+                let [cx, cy] = self.screen_center();
+                self.add_animation(
+                    format!("Player 2 left ({})", reason),
+                    [cx, cy],
+                    999,
+                    48.0,
+                    Color::RED,
+                );
+                self.add_animation(
+                    "Waiting for another player".to_string(),
+                    [cx, cy + 60.0],
+                    999,
+                    36.0,
+                    Color::WHITE,
+                );
+            }
+        }
+    }
+
+    // One frame once the inputs are read, without touching the window, so a
+    // headless game runs exactly what the windowed one does
+    pub fn frame(&mut self, elapsed: Duration, live: (Input, Input)) {
+        self.animations.retain(|anim| anim.is_active()); // Retain only active animations
+        self.particles.update(elapsed.as_secs_f32());
+        self.camera.update(elapsed.as_secs_f32());
+        let [cx, cy] = self.screen_center();
+
+        // A client only shows what the host sends
+        if self.is_client() {
+            self.update_client(live.0);
+            return;
+        }
+        self.poll_host();
+
+        match self.sim.state {
+            // The demo keeps playing until someone takes over
+            GameState::GameOver if self.autopilot.is_some() => self.reset(),
+            GameState::GameOver | GameState::Paused | GameState::Editor => {}
+            GameState::MainMenu => {
+                if self.rebinding.is_some() {
+                    self.menu_idle_since = Instant::now();
+                } else if self.menu_idle_since.elapsed() >= ATTRACT_DELAY {
+                    self.start_demo(Difficulty::Normal);
+                }
+            }
+            GameState::Countdown => {
+                if let Some(start_time) = self.countdown_start
+                    && Instant::now().duration_since(start_time) >= Duration::from_secs(1)
+                {
+                    self.countdown_value -= 1;
+                    self.countdown_start = Some(Instant::now());
+                    // Add countdown animation without clearing existing ones
+                    if self.countdown_value > 0 {
+                        self.animations.clear(); // Clear only before adding "Game Start!" ??
+
+                        self.add_animation(
+                            format!("{}", self.countdown_value),
+                            [cx, cy - 100.0], // Adjusted position
+                            1,
+                            96.0,
+                            Color::CYAN,
+                        );
+                        self.animations.clear(); // Clear only before adding "Game Start!" ??
+                    } else {
+                        self.sim.state = GameState::Playing;
+                        self.sounds.play(Sound::Start);
+                        self.animations.clear(); // Clear only before adding "Game Start!"
+                        self.add_animation(
+                            "Game Start!".to_string(),
+                            [cx, cy - 50.0], // Adjusted position
+                            2,
+                            72.0,
+                            Color::GREEN,
+                        );
+                        self.add_animation(
+                            format!("Press {} to pause", self.bindings.describe(Action::Pause)),
+                            [cx, cy + 50.0], // Adjusted position
+                            3,
+                            24.0,
+                            Color::WHITE,
+                        );
+                    }
+                }
+            }
+            GameState::Playing => self.advance(elapsed, live),
+        }
+        // Every frame, so a host keeps sending snapshots while paused
+        self.handle_sim_events();
+    }
+
+    // Send what this player is holding and show the host's latest state,
+    // smoothed between snapshots
+    fn update_client(&mut self, live: Input) {
+        let now = Instant::now();
+        let Some(Network::Client(client)) = &mut self.net else {
+            return;
+        };
+        let events = match client
+            .poll(now)
+            .and_then(|events| client.send_input(live).map(|()| events))
+        {
+            Ok(events) => events,
+            Err(e) => {
+                self.net = Some(Network::Disconnected);
+                self.sim.state = GameState::GameOver;
+                self.animations.clear();
+                let [cx, cy] = self.screen_center();
+                self.add_animation(
+                    "Disconnected from host".to_string(),
+                    [cx, cy - 50.0],
+                    999,
+                    72.0,
+                    Color::RED,
+                );
+                self.add_animation(e.to_string(), [cx, cy + 50.0], 999, 28.0, Color::WHITE);
+                self.add_animation(
+                    format!(
+                        "Press {} to play here or {} for the menu",
+                        self.bindings.describe(Action::Restart),
+                        self.bindings.describe(Action::Menu)
+                    ),
+                    [cx, cy + 100.0],
+                    999,
+                    28.0,
+                    Color::YELLOW,
+                );
+                return;
+            }
+        };
+        if let Some(snapshot) = client.snapshot(now) {
+            let before = self.sim.state;
+            snapshot.apply(&mut self.sim);
+            self.countdown_value = snapshot.countdown;
+            if before != self.sim.state {
+                self.host_state_changed(before);
+            }
+        }
+        self.show_events(events);
+    }
+
+    // The banners the host shows for its own state changes
+    fn host_state_changed(&mut self, before: GameState) {
+        let [cx, cy] = self.screen_center();
+        match self.sim.state {
+            GameState::Countdown => {
+                self.animations.clear();
+                self.add_animation(
+                    "Get Ready!".to_string(),
+                    [cx, cy - 50.0],
+                    1,
+                    72.0,
+                    Color::CYAN,
+                );
+            }
+            GameState::Playing if before == GameState::Countdown => {
+                self.sounds.play(Sound::Start);
+                self.animations.clear();
+                self.add_animation(
+                    "Game Start!".to_string(),
+                    [cx, cy - 50.0],
+                    2,
+                    72.0,
+                    Color::GREEN,
+                );
+            }
+            GameState::Playing => self.animations.clear(),
+            GameState::Paused => {
+                self.animations.clear();
+                self.add_animation("PAUSED".to_string(), [cx, cy], 999, 72.0, Color::CYAN);
+            }
+            GameState::GameOver | GameState::Editor | GameState::MainMenu => {}
+        }
+    }
+
+    // A run taken over from a replay has the replay's seed, so it gets a name
+    // of its own rather than overwriting the file that was being watched
+    pub fn save_replay(&self) {
+        let file_name = if self.taken_over {
+            self.recording.takeover_file_name()
+        } else {
+            self.recording.file_name()
+        };
+        let path = self.replay_dir.join(file_name);
+        match self.recording.save(&path) {
+            Ok(()) => println!("replay saved to {}", path.display()),
+            Err(e) => eprintln!("Failed to save replay to {}: {}", path.display(), e),
+        }
+    }
+
+    // Play-tests and demos don't count towards high scores or replays, and
+    // of a network match only the host, which ran it, keeps the replay
+    pub fn keeps_records(&self) -> bool {
+        self.playback.is_none()
+            && self.campaign.is_none()
+            && self.autopilot.is_none()
+            && !self.is_client()
+    }
+
+    // A run left before Game Over, by restarting, quitting or going to the
+    // menu or editor, is saved as far as it got. Finished runs were already
+    // saved on Game Over.
+    pub fn save_unfinished_replay(&self) {
+        if matches!(
+            self.sim.state,
+            GameState::Countdown | GameState::Playing | GameState::Paused
+        ) && !self.recording.inputs.is_empty()
+            && self.keeps_records()
+        {
+            self.save_replay();
+        }
+    }
+
+    pub fn save_high_score(&mut self) {
+        if self.high_scores.record(self.sim.score)
+            && let Err(e) = self.high_scores.save(&self.high_scores_path)
+        {
+            eprintln!(
+                "Failed to save high scores to {}: {}",
+                self.high_scores_path.display(),
+                e
+            );
+        }
+    }
+
+    // Turn simulation events into feedback here and, when hosting, on the
+    // other player's screen too
+    pub fn handle_sim_events(&mut self) {
+        let events = self.sim.drain_events();
+        if let Some(Network::Host(host)) = &mut self.net {
+            let snapshot = Snapshot::capture(&self.sim, self.countdown_value, events.clone());
+            for event in host.send_snapshot(snapshot) {
+                self.host_event(event);
+            }
+        }
+        self.show_events(events);
+    }
+
+    // On-screen and audio feedback for simulation events
+    pub fn show_events(&mut self, events: Vec<SimEvent>) {
+        let [cx, cy] = self.screen_center();
+        for event in events {
+            match event {
+                SimEvent::BarHit => {
+                    self.combo = 0;
+                    self.sounds.play(Sound::Bounce);
+                }
+                SimEvent::NewHighScore(score) => {
+                    self.animations.clear(); // Clear previous animations
+                    self.add_animation(
+                        format!("New High Score: {}!", score),
+                        [cx, cy - 70.0], // Adjusted position
+                        2,
+                        48.0,
+                        Color::CYAN,
+                    );
+                }
+                SimEvent::ExtraHeart => {
+                    self.sounds.play(Sound::Heart);
+                    self.add_animation(
+                        "Extra Heart Awarded!".to_string(),
+                        [cx, cy - 120.0], // Adjusted position
+                        2,
+                        36.0,
+                        Color::GREEN,
+                    );
+                }
+                SimEvent::HeartLost { player, remaining } => {
+                    self.sounds.play(Sound::Heart);
+                    self.camera.add_trauma(HEART_LOST_TRAUMA);
+                    self.combo = 0;
+                    self.animations.clear(); // Clear existing animations
+                    let text = if self.sim.player_two.is_some() {
+                        format!("{} lost a heart! {} remaining", player.label(), remaining)
+                    } else {
+                        format!("Lost a heart! {} remaining", remaining)
+                    };
+                    self.add_animation(
+                        text,
+                        [cx, cy], // Adjusted position
+                        2,
+                        48.0,
+                        Color::RED,
+                    );
+                }
+                SimEvent::GameOver => {
+                    self.sounds.play(Sound::GameOver);
+                    self.camera.add_trauma(GAME_OVER_TRAUMA);
+                    self.camera.pulse_zoom();
+                    // Matches between two players aren't high score runs, and
+                    // neither are runs mostly played by a replay
+                    if self.keeps_records() {
+                        if self.sim.player_two.is_none() && !self.taken_over {
+                            self.save_high_score();
+                        }
+                        self.save_replay();
+                    }
+                    self.animations.clear(); // Clear existing animations
+                    let title = match self.sim.winner() {
+                        Some(winner) => format!("{} wins!", winner.label()),
+                        None => "Game Over!".to_string(),
+                    };
+                    self.add_animation(
+                        title,
+                        [cx, cy - 50.0], // Adjusted position
+                        999,
+                        72.0,
+                        Color::RED,
+                    );
+                    let retry = if self.is_client() {
+                        "Waiting for the host to start again".to_string()
+                    } else if self.net.is_none() && self.campaign.is_none() {
+                        format!(
+                            "Press {} to retry or {} for the menu",
+                            self.bindings.describe(Action::Restart),
+                            self.bindings.describe(Action::Menu)
+                        )
+                    } else {
+                        format!("Press {} to retry", self.bindings.describe(Action::Restart))
+                    };
+                    self.add_animation(
+                        retry,
+                        [cx, cy + 50.0], // Adjusted position
+                        999,
+                        36.0,
+                        Color::WHITE,
+                    );
+                    if self.campaign.is_some() {
+                        self.add_animation(
+                            "Esc returns to the editor".to_string(),
+                            [cx, cy + 100.0],
+                            999,
+                            28.0,
+                            Color::YELLOW,
+                        );
+                    }
+                }
+                SimEvent::PowerUpCollected(power_type) => {
+                    if power_type == PowerUpType::ExtraHeart {
+                        self.sounds.play(Sound::Heart);
+                    }
+                    self.add_animation(
+                        format!("{}!", power_type.label()),
+                        [cx, cy + 120.0],
+                        2,
+                        36.0,
+                        power_up_color(power_type),
+                    );
+                }
+                SimEvent::PowerUpExpired(power_type) => {
+                    self.add_animation(
+                        format!("{} wore off", power_type.label()),
+                        [cx, cy + 120.0],
+                        2,
+                        24.0,
+                        Color::WHITE,
+                    );
+                }
+                SimEvent::BrickHit => self.sounds.play(Sound::Bounce),
+                SimEvent::BrickDestroyed { .. } => {
+                    self.sounds.play(Sound::Bounce);
+                    self.combo += 1;
+                    if self.combo >= COMBO_SHAKE_MIN {
+                        self.camera.add_trauma(COMBO_TRAUMA);
+                    }
+                }
+                SimEvent::Impact {
+                    x,
+                    y,
+                    normal,
+                    surface,
+                } => {
+                    let color = match surface {
+                        Surface::Bar => Color::GREEN,
+                        Surface::Wall => Color::WHITE,
+                        Surface::Brick => Color::new(1.0, 0.5, 0.2, 1.0),
+                    };
+                    self.particles.burst(x, y, normal, 8, color);
+                }
+                SimEvent::BallLost { x, y } => {
+                    self.particles
+                        .explode(x, y, 60, Color::new(1.0, 0.4, 0.1, 1.0));
+                }
+                SimEvent::StageCleared { next_stage } => {
+                    self.sounds.play(Sound::Start);
+                    self.animations.clear();
+                    let title = match self.sim.current_level() {
+                        Some(level) => format!("Stage {}: {}", next_stage, level.name),
+                        None => format!("Stage {}!", next_stage),
+                    };
+                    self.add_animation(title, [cx, cy], 2, 72.0, Color::GREEN);
+                }
+            }
+        }
+    }
+}
+
+impl EventHandler for Game {
+    fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let live = if self.is_client() || self.sim.state == GameState::Playing {
+            self.live_inputs(ctx)
+        } else {
+            (Input::default(), Input::default())
+        };
+        self.frame(ctx.time.delta(), live);
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
+        // Clear the screen with a black background to remove previous frames
+        let mut canvas = graphics::Canvas::from_frame(ctx, Color::BLACK);
+        let [cx, cy] = self.screen_center();
+
+        if self.sim.state == GameState::MainMenu {
+            self.draw_menu(ctx, &mut canvas);
+            self.draw_rebind_prompt(ctx, &mut canvas);
+            canvas.finish(ctx)?;
+            return Ok(());
+        }
+
+        if self.sim.state == GameState::Editor {
+            self.draw_editor(ctx, &mut canvas)?;
+            self.draw_animations(ctx, &mut canvas);
+            canvas.finish(ctx)?;
+            return Ok(());
+        }
+
+        // Shake and zoom move everything below, HUD included
+        if let Some(screen) = canvas.screen_coordinates() {
+            canvas.set_screen_coordinates(self.camera.view(screen));
+        }
+
+        // Draw score and lives (top left), for both players in two-player mode
+        let mut hud = match &self.sim.player_two {
+            Some(player_two) => format!(
+                "{}: {} points, {} hearts\n{}: {} points, {} hearts",
+                Player::One.label(),
+                self.sim.score,
+                self.sim.hearts,
+                Player::Two.label(),
+                player_two.score,
+                player_two.hearts
+            ),
+            None => format!(
+                "Score: {}\nHearts: {}\nHigh Score: {}",
+                self.sim.score, self.sim.hearts, self.sim.high_score
+            ),
+        };
+        if let Some(level) = self.sim.current_level() {
+            hud.push_str(&format!("\nStage {}: {}", self.sim.stage, level.name));
+        } else if self.sim.has_stages() {
+            hud.push_str(&format!("\nStage: {}", self.sim.stage));
+        }
+        let score_text = Text::new(TextFragment::new(hud).scale(24.0));
+        score_text.draw(&mut canvas, DrawParam::default().dest([20.0, 20.0]));
+
+        // Draw running power-ups and their time left under the score
+        let mut effects_text = Text::default();
+        for effect in &self.sim.effects {
+            let seconds = self
+                .sim
+                .effect_ticks_left(effect.power_type)
+                .unwrap_or(0)
+                .div_ceil(TICK_RATE as u64);
+            effects_text.add(
+                TextFragment::new(format!("{} {}s\n", effect.power_type.label(), seconds))
+                    .scale(20.0)
+                    .color(power_up_color(effect.power_type)),
+            );
+        }
+        effects_text.draw(&mut canvas, DrawParam::default().dest([20.0, 140.0]));
+
+        // Draw controls (top right)
+        let controls_text = Text::new(TextFragment::new(self.controls_text()).scale(20.0));
+        controls_text.draw(
+            &mut canvas,
+            DrawParam::default().dest([self.sim.config.width - 300.0, 20.0]),
+        );
+
+        // Draw countdown or game elements
+        if self.sim.state == GameState::Countdown {
+            let countdown_text = Text::new(
+                TextFragment::new(format!("{}", self.countdown_value))
+                    .scale(96.0)
+                    .color(Color::CYAN),
+            );
+            let dims = countdown_text.dimensions(ctx).unwrap();
+            countdown_text.draw(
+                &mut canvas,
+                DrawParam::default().dest([cx - dims.w / 2.0, cy - dims.h / 2.0]),
+            );
+        } else {
+            // Draw game objects
+            if !self.sim.balls.is_empty() {
+                let mut builder = graphics::MeshBuilder::new();
+                for ball in &self.sim.balls {
+                    builder.rectangle(
+                        graphics::DrawMode::fill(),
+                        graphics::Rect::new(ball.x, ball.y, ball.size, ball.size),
+                        Color::WHITE,
+                    )?;
+                }
+                let balls = graphics::Mesh::from_data(ctx, builder.build());
+                balls.draw(&mut canvas, DrawParam::default());
+            }
+
+            let bar_rect = graphics::Rect::new(
+                self.sim.bar.x,
+                self.sim.bar.y,
+                self.sim.bar.width,
+                self.sim.bar.height,
+            );
+            let bar = graphics::Mesh::new_rectangle(
+                ctx,
+                graphics::DrawMode::fill(),
+                bar_rect,
+                Color::GREEN,
+            )?;
+            bar.draw(&mut canvas, DrawParam::default());
+
+            if let Some(player_two) = &self.sim.player_two {
+                let top = &player_two.bar;
+                let top_bar = graphics::Mesh::new_rectangle(
+                    ctx,
+                    graphics::DrawMode::fill(),
+                    graphics::Rect::new(top.x, top.y, top.width, top.height),
+                    Color::CYAN,
+                )?;
+                top_bar.draw(&mut canvas, DrawParam::default());
+            }
+
+            // One mesh for the whole grid; tougher bricks are brighter and
+            // obstacles grey
+            if !self.sim.bricks.is_empty() || !self.sim.obstacles.is_empty() {
+                let mut builder = graphics::MeshBuilder::new();
+                for obstacle in &self.sim.obstacles {
+                    builder.rectangle(
+                        graphics::DrawMode::fill(),
+                        graphics::Rect::new(obstacle.x, obstacle.y, obstacle.w, obstacle.h),
+                        Color::new(0.5, 0.5, 0.5, 1.0),
+                    )?;
+                }
+                for brick in &self.sim.bricks {
+                    let strength = brick.hp as f32 / brick.max_hp.max(1) as f32;
+                    let shade = 0.4 + 0.6 * strength;
+                    builder.rectangle(
+                        graphics::DrawMode::fill(),
+                        graphics::Rect::new(brick.x, brick.y, brick.width, brick.height),
+                        Color::new(shade, 0.5 * shade, 0.2, 1.0),
+                    )?;
+                }
+                let bricks = graphics::Mesh::from_data(ctx, builder.build());
+                bricks.draw(&mut canvas, DrawParam::default());
+            }
+
+            for power_up in &self.sim.power_ups {
+                let power_up_rect =
+                    graphics::Rect::new(power_up.x, power_up.y, power_up.size, power_up.size);
+                let power_up_mesh = graphics::Mesh::new_rectangle(
+                    ctx,
+                    graphics::DrawMode::fill(),
+                    power_up_rect,
+                    power_up_color(power_up.power_type),
+                )?;
+                power_up_mesh.draw(&mut canvas, DrawParam::default());
+            }
+        }
+
+        if self.autopilot.is_some() {
+            let banner = Text::new(
+                TextFragment::new("DEMO - press any key for the menu")
+                    .scale(36.0)
+                    .color(Color::YELLOW),
             );
-            let dims = text.dimensions(ctx).unwrap();
-            text.draw(
+            let dims = banner.dimensions(ctx).unwrap();
+            banner.draw(
                 &mut canvas,
-                DrawParam::default().dest([
-                    anim.position[0] - dims.w / 2.0,
-                    anim.position[1] - dims.h / 2.0,
-                ]),
+                DrawParam::default().dest([cx - dims.w / 2.0, self.sim.config.height - 120.0]),
             );
         }
 
+        // Every particle in one instanced draw
+        if !self.particles.alive().is_empty() {
+            let offset = PARTICLE_SIZE / 2.0;
+            let batch = self
+                .particle_batch
+                .get_or_insert_with(|| InstanceArray::new(ctx, None));
+            batch.set(self.particles.alive().iter().map(|particle| {
+                DrawParam::default()
+                    .dest([particle.x - offset, particle.y - offset])
+                    .scale([PARTICLE_SIZE, PARTICLE_SIZE])
+                    .color(particle.current_color())
+            }));
+            canvas.draw(&*batch, DrawParam::default());
+        }
+
+        self.draw_rebind_prompt(ctx, &mut canvas);
+
+        self.draw_animations(ctx, &mut canvas);
+
         // Finish and present the frame
         canvas.finish(ctx)?;
         Ok(())
@@ -380,102 +1846,849 @@ impl EventHandler for Game {
         key_input: KeyInput,
         _repeat: bool,
     ) -> GameResult {
-        match key_input.keycode {
-            Some(KeyCode::P) | Some(KeyCode::Space) => {
-                if self.state == GameState::Playing {
-                    self.state = GameState::Paused;
-                    self.add_animation(
-                        "PAUSED".to_string(),
-                        [WIDTH / 2.0, HEIGHT / 2.0],
-                        999,
-                        72.0,
-                        Color::CYAN,
-                    );
-                } else if self.state == GameState::Paused {
-                    self.state = GameState::Playing;
+        let Some(key) = key_input.keycode else {
+            return Ok(());
+        };
+        if let Some(action) = self.rebinding {
+            self.rebind_next(action, key);
+            return Ok(());
+        }
+        if self.autopilot.is_some() {
+            self.stop_demo();
+            return Ok(());
+        }
+        if self.sim.state == GameState::MainMenu {
+            if self.menu_key(ctx, key) {
+                return Ok(());
+            }
+        } else if self.sim.state == GameState::Editor {
+            if self.editor_key(key) {
+                return Ok(());
+            }
+        } else if key == KeyCode::Escape && self.campaign.is_some() {
+            self.return_to_editor();
+            return Ok(());
+        }
+        match self.bindings.action_for(key) {
+            Some(action) => self.handle_action(ctx, action),
+            None => Ok(()),
+        }
+    }
+
+    fn quit_event(&mut self, _ctx: &mut Context) -> GameResult<bool> {
+        self.save_unfinished_replay();
+        Ok(false)
+    }
+
+    fn mouse_motion_event(
+        &mut self,
+        ctx: &mut Context,
+        x: f32,
+        y: f32,
+        _dx: f32,
+        _dy: f32,
+    ) -> GameResult {
+        // Dragging paints or erases a line of cells
+        if self.sim.state == GameState::Editor {
+            if ctx.mouse.button_pressed(MouseButton::Left) {
+                self.edit_cell(x, y, MouseButton::Left);
+            } else if ctx.mouse.button_pressed(MouseButton::Right) {
+                self.edit_cell(x, y, MouseButton::Right);
+            }
+            return Ok(());
+        }
+        self.point_at(ctx, x, y);
+        Ok(())
+    }
+
+    // Also where a touch starts; moving the finger arrives as mouse motion
+    fn mouse_button_down_event(
+        &mut self,
+        ctx: &mut Context,
+        button: MouseButton,
+        x: f32,
+        y: f32,
+    ) -> GameResult {
+        if self.sim.state == GameState::Editor {
+            self.edit_cell(x, y, button);
+            return Ok(());
+        }
+        if self.autopilot.is_some() {
+            self.stop_demo();
+            return Ok(());
+        }
+        self.point_at(ctx, x, y);
+        Ok(())
+    }
+
+    fn gamepad_button_down_event(
+        &mut self,
+        ctx: &mut Context,
+        btn: Button,
+        id: GamepadId,
+    ) -> GameResult {
+        if self.autopilot.is_some() {
+            self.stop_demo();
+            return Ok(());
+        }
+        if self.sim.state == GameState::MainMenu
+            && self.rebinding.is_none()
+            && self.menu_button(ctx, btn)
+        {
+            return Ok(());
+        }
+        let pad = self.pad_for(id);
+        match btn {
+            Button::DPadLeft => pad.left = true,
+            Button::DPadRight => pad.right = true,
+            // Rebinding only listens to the keyboard
+            _ if self.rebinding.is_some() => {}
+            _ => {
+                if let Some(action) = gamepad_action(btn) {
+                    self.handle_action(ctx, action)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn gamepad_button_up_event(
+        &mut self,
+        _ctx: &mut Context,
+        btn: Button,
+        id: GamepadId,
+    ) -> GameResult {
+        let pad = self.pad_for(id);
+        match btn {
+            Button::DPadLeft => pad.left = false,
+            Button::DPadRight => pad.right = false,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn gamepad_axis_event(
+        &mut self,
+        _ctx: &mut Context,
+        axis: Axis,
+        value: f32,
+        id: GamepadId,
+    ) -> GameResult {
+        if matches!(axis, Axis::LeftStickX | Axis::DPadX) {
+            self.pad_for(id).axis = Input::from_stick(value).axis;
+        }
+        Ok(())
+    }
+}
+
+impl Game {
+    // One-shot actions; movement is read every tick in Input::from_keyboard
+    pub fn handle_action(&mut self, ctx: &mut Context, action: Action) -> GameResult {
+        match action {
+            // With the host gone a client plays on here, or goes to the menu
+            Action::Restart | Action::Menu if matches!(self.net, Some(Network::Disconnected)) => {
+                self.net = None;
+                if action == Action::Restart {
+                    self.start_mode(self.sim.config.two_player);
+                } else {
+                    self.open_menu();
+                }
+            }
+            // The host runs the match, so only the host pauses and restarts it
+            Action::Pause | Action::Restart | Action::Rebind if self.is_client() => {}
+            Action::Pause => {
+                if self.sim.state == GameState::Playing {
+                    self.sim.state = GameState::Paused;
+                    let [cx, cy] = self.screen_center();
+                    self.add_animation("PAUSED".to_string(), [cx, cy], 999, 72.0, Color::CYAN);
+                } else if self.sim.state == GameState::Paused && !self.waiting_for_peer() {
+                    self.sim.state = GameState::Playing;
                     self.animations.clear();
                 }
             }
-            Some(KeyCode::F) => {
+            Action::Fullscreen => {
                 self.fullscreen = !self.fullscreen;
+                let windowed =
+                    WindowMode::default().dimensions(self.sim.config.width, self.sim.config.height);
                 let mode = if self.fullscreen {
-                    WindowMode::default().fullscreen_type(ggez::conf::FullscreenType::True)
+                    windowed.fullscreen_type(ggez::conf::FullscreenType::True)
                 } else {
-                    WindowMode::default().fullscreen_type(ggez::conf::FullscreenType::Windowed)
+                    windowed.fullscreen_type(ggez::conf::FullscreenType::Windowed)
                 };
                 ctx.gfx.set_mode(mode).expect("Failed to toggle fullscreen");
             }
-            Some(KeyCode::R) if self.state == GameState::GameOver => {
-                self.prev_high_score = self.high_score;
+            Action::Restart
+                if self.sim.state == GameState::GameOver && !self.waiting_for_peer() =>
+            {
+                self.sim.prev_high_score = self.sim.high_score;
                 self.reset();
             }
+            Action::Rebind if self.sim.state == GameState::Paused => {
+                self.rebinding = Some(Action::ALL[0]);
+            }
+            // Levels aren't used in two-player matches, so there is nothing to edit
+            Action::Editor
+                if matches!(self.sim.state, GameState::Paused | GameState::GameOver)
+                    && self.playback.is_none()
+                    && self.sim.player_two.is_none() =>
+            {
+                self.open_editor();
+            }
+            // Network games have nowhere to go back to, and play-tests go
+            // back to the editor instead
+            Action::Menu
+                if matches!(self.sim.state, GameState::Paused | GameState::GameOver)
+                    && self.net.is_none()
+                    && self.campaign.is_none() =>
+            {
+                self.open_menu();
+            }
+            Action::Mute => {
+                self.audio_settings.toggle_mute();
+                self.audio_settings_changed();
+            }
+            Action::VolumeDown => {
+                self.audio_settings.adjust(Channel::Master, -VOLUME_STEP);
+                self.audio_settings_changed();
+            }
+            Action::VolumeUp => {
+                self.audio_settings.adjust(Channel::Master, VOLUME_STEP);
+                self.audio_settings_changed();
+            }
             _ => {}
         }
         Ok(())
     }
-}
 
-impl Default for Ball {
-    fn default() -> Self {
-        Self::new()
+    // In a two-player match the gamepads are shared out by Player::for_gamepad;
+    // with one player every gamepad steers the same bar
+    fn pad_for(&mut self, id: GamepadId) -> &mut Input {
+        let order = match self.gamepads.iter().position(|&pad| pad == id) {
+            Some(order) => order,
+            None => {
+                self.gamepads.push(id);
+                self.gamepads.len() - 1
+            }
+        };
+        let keyboard_player_one = self.keyboard_player_one && self.net.is_none();
+        if self.sim.player_two.is_some()
+            && Player::for_gamepad(order, keyboard_player_one) == Player::Two
+        {
+            &mut self.pad_two
+        } else {
+            &mut self.pad
+        }
+    }
+
+    // Apply new levels right away, show them and keep them for next time
+    pub fn audio_settings_changed(&mut self) {
+        self.sounds.apply_settings(&self.audio_settings);
+        let text = if self.audio_settings.muted {
+            "Muted".to_string()
+        } else {
+            format!(
+                "Volume {:.0}%",
+                self.audio_settings.volume(Channel::Master) * 100.0
+            )
+        };
+        let [cx, _] = self.screen_center();
+        self.animations
+            .retain(|anim| !anim.text.starts_with("Volume") && anim.text != "Muted");
+        self.add_animation(text, [cx, 60.0], 1, 32.0, Color::WHITE);
+        if let Err(e) = self.audio_settings.save(&self.audio_settings_path) {
+            eprintln!(
+                "Failed to save audio settings to {}: {}",
+                self.audio_settings_path.display(),
+                e
+            );
+        }
+    }
+
+    // Rebinding walks through every action in turn; Escape keeps the current keys
+    pub fn rebind_next(&mut self, action: Action, key: KeyCode) {
+        if key != KeyCode::Escape {
+            self.bindings.rebind(action, key);
+        }
+        let next = Action::ALL.iter().position(|&a| a == action).map(|i| i + 1);
+        self.rebinding = next.and_then(|i| Action::ALL.get(i).copied());
+    }
+
+    pub fn controls_text(&self) -> String {
+        let mut text = "Controls:".to_string();
+        for action in Action::ALL {
+            text.push_str(&format!(
+                "\n{} - {}",
+                self.bindings.describe(action),
+                action.label()
+            ));
+        }
+        text
+    }
+
+    // Drawn below the pause banner, or over the settings page
+    fn draw_rebind_prompt(&self, ctx: &mut Context, canvas: &mut graphics::Canvas) {
+        let [cx, cy] = self.screen_center();
+        if let Some(action) = self.rebinding {
+            let prompt = Text::new(
+                TextFragment::new(format!(
+                    "Press a key for {} (Esc keeps {})",
+                    action.label(),
+                    self.bindings.describe(action)
+                ))
+                .scale(32.0)
+                .color(Color::YELLOW),
+            );
+            let dims = prompt.dimensions(ctx).unwrap();
+            prompt.draw(
+                canvas,
+                DrawParam::default().dest([cx - dims.w / 2.0, cy + 80.0]),
+            );
+        }
+    }
+
+    // Back to the title screen, dropping any replay being watched
+    pub fn open_menu(&mut self) {
+        self.save_unfinished_replay();
+        self.playback = None;
+        if std::mem::take(&mut self.taken_over) {
+            self.restore_high_score();
+        }
+        self.sim.state = GameState::MainMenu;
+        self.menu = Menu::default();
+        self.menu_idle_since = Instant::now();
+        self.animations.clear();
+        self.particles.clear();
+        self.camera.settle();
+    }
+
+    // Keys the menu uses through their bound actions: the move keys step a
+    // volume, Pause picks like Menu Select and Main Menu goes back. Returns
+    // false for keys it doesn't use.
+    pub fn menu_key(&mut self, ctx: &mut Context, key: KeyCode) -> bool {
+        match self.bindings.action_for(key) {
+            Some(Action::MenuUp) => self.menu.move_up(),
+            Some(Action::MenuDown) => self.menu.move_down(),
+            Some(Action::MenuSelect | Action::Pause) => {
+                let item = self.menu.confirm();
+                self.choose(ctx, item);
+            }
+            Some(Action::MoveLeft) => return self.menu_adjust(-1.0),
+            Some(Action::MoveRight) => return self.menu_adjust(1.0),
+            Some(Action::Menu) => return self.menu.back(),
+            _ => return false,
+        }
+        self.menu_idle_since = Instant::now();
+        true
+    }
+
+    // The d-pad moves through the menu, South or Start picks and East goes back
+    pub fn menu_button(&mut self, ctx: &mut Context, button: Button) -> bool {
+        match button {
+            Button::DPadUp => self.menu.move_up(),
+            Button::DPadDown => self.menu.move_down(),
+            Button::DPadLeft => return self.menu_adjust(-1.0),
+            Button::DPadRight => return self.menu_adjust(1.0),
+            Button::South | Button::Start => {
+                let item = self.menu.confirm();
+                self.choose(ctx, item);
+            }
+            Button::East => {
+                self.menu.back();
+            }
+            _ => return false,
+        }
+        self.menu_idle_since = Instant::now();
+        true
+    }
+
+    // Left and right step the volume entry under the cursor; false anywhere else
+    fn menu_adjust(&mut self, direction: f32) -> bool {
+        let MenuItem::Volume(channel) = self.menu.selected_item() else {
+            return false;
+        };
+        self.audio_settings.adjust(channel, direction * VOLUME_STEP);
+        self.audio_settings_changed();
+        self.menu_idle_since = Instant::now();
+        true
+    }
+
+    // What picking a menu entry does, past moving between pages
+    fn choose(&mut self, ctx: &mut Context, item: MenuItem) {
+        match item {
+            MenuItem::Play => self.start_mode(self.sim.config.two_player),
+            MenuItem::OnePlayer => self.start_mode(false),
+            MenuItem::TwoPlayers => self.start_mode(true),
+            MenuItem::Demo => self.start_demo(Difficulty::Normal),
+            MenuItem::ScreenShake => {
+                self.camera.enabled = !self.camera.enabled;
+                self.camera.settle();
+                self.display_settings.screen_shake = self.camera.enabled;
+                if let Err(e) = self.display_settings.save(&self.display_settings_path) {
+                    eprintln!(
+                        "Failed to save display settings to {}: {}",
+                        self.display_settings_path.display(),
+                        e
+                    );
+                }
+            }
+            MenuItem::Sound => {
+                self.audio_settings.toggle_mute();
+                self.audio_settings_changed();
+            }
+            MenuItem::RebindKeys => self.rebinding = Some(Action::ALL[0]),
+            MenuItem::Quit => ctx.request_quit(),
+            MenuItem::Modes
+            | MenuItem::Settings
+            | MenuItem::HighScores
+            | MenuItem::Volume(_)
+            | MenuItem::Back => {}
+        }
+    }
+
+    // Start a run, switching between one and two players first if needed
+    pub fn start_mode(&mut self, two_player: bool) {
+        self.switch_mode(two_player);
+        self.restore_high_score();
+        self.reset();
+    }
+
+    fn switch_mode(&mut self, two_player: bool) {
+        if self.sim.config.two_player != two_player {
+            let mut config = self.sim.config.clone();
+            config.two_player = two_player;
+            self.sim = Simulation::new(config, self.sim.seed);
+            // The last run was saved when it was left, so the new one starts clean
+            self.recording = Replay::start(&self.sim);
+        }
+    }
+
+    fn menu_label(&self, item: MenuItem) -> String {
+        match item {
+            MenuItem::Play if self.sim.config.two_player => "Play (Two Players)".to_string(),
+            MenuItem::ScreenShake => {
+                let state = if self.camera.enabled { "On" } else { "Off" };
+                format!("{}: {}", item.label(), state)
+            }
+            MenuItem::Sound => {
+                let state = if self.audio_settings.muted {
+                    "Muted"
+                } else {
+                    "On"
+                };
+                format!("{}: {}", item.label(), state)
+            }
+            MenuItem::Volume(channel) => format!(
+                "< {}: {:.0}% >",
+                item.label(),
+                self.audio_settings.volume(channel) * 100.0
+            ),
+            _ => item.label().to_string(),
+        }
+    }
+
+    fn draw_menu(&self, ctx: &mut Context, canvas: &mut graphics::Canvas) {
+        let [cx, cy] = self.screen_center();
+        let mut centred = |text: Text, y: f32| {
+            let dims = text.dimensions(ctx).unwrap();
+            text.draw(canvas, DrawParam::default().dest([cx - dims.w / 2.0, y]));
+        };
+
+        let page = self.menu.page;
+        let title_scale = if page == MenuPage::Main { 96.0 } else { 72.0 };
+        centred(
+            Text::new(
+                TextFragment::new(page.title())
+                    .scale(title_scale)
+                    .color(Color::CYAN),
+            ),
+            cy - 300.0,
+        );
+
+        let mut y = cy - 120.0;
+        if page == MenuPage::HighScores {
+            let mut lines: Vec<String> = self
+                .high_scores
+                .scores
+                .iter()
+                .enumerate()
+                .map(|(rank, score)| format!("{}. {}", rank + 1, score))
+                .collect();
+            if lines.is_empty() {
+                lines.push("No scores yet".to_string());
+            }
+            for line in lines {
+                centred(Text::new(TextFragment::new(line).scale(32.0)), y);
+                y += 40.0;
+            }
+            y += 40.0;
+        }
+
+        for (index, &item) in page.items().iter().enumerate() {
+            let label = self.menu_label(item);
+            let entry = if index == self.menu.selected {
+                TextFragment::new(format!("> {} <", label))
+                    .scale(48.0)
+                    .color(Color::CYAN)
+            } else {
+                TextFragment::new(label).scale(40.0).color(Color::WHITE)
+            };
+            centred(Text::new(entry), y);
+            y += 64.0;
+        }
+
+        centred(
+            Text::new(
+                TextFragment::new(
+                    "Up/Down to choose, Left/Right to change, Enter to select, Esc to go back",
+                )
+                .scale(24.0)
+                .color(Color::new(0.6, 0.6, 0.6, 1.0)),
+            ),
+            self.sim.config.height - 80.0,
+        );
+    }
+
+    // Pick up the saved custom level, or a blank one, unless already editing
+    pub fn open_editor(&mut self) {
+        self.save_unfinished_replay();
+        if self.editor.is_none() {
+            let dir = self.levels_dir();
+            let path = dir.join(EDITOR_FILE);
+            match Editor::open(&path, &self.sim.config) {
+                Ok(editor) => self.editor = Some(editor),
+                Err(e) => {
+                    eprintln!("Can't open the level editor: {}", e);
+                    self.editor_message(e.to_string(), Color::RED);
+                    return;
+                }
+            }
+        }
+        self.sim.state = GameState::Editor;
+        self.animations.clear();
+    }
+
+    // The level directory being played, or the usual one with level files off
+    fn levels_dir(&self) -> &Path {
+        let dir = &self.sim.config.levels_dir;
+        if dir.as_os_str().is_empty() {
+            Path::new(LEVELS_DIR)
+        } else {
+            dir
+        }
+    }
+
+    // Editor keys come before the bindings; returns false for keys it doesn't use
+    pub fn editor_key(&mut self, key: KeyCode) -> bool {
+        let Some(editor) = &mut self.editor else {
+            return false;
+        };
+        let config = &self.sim.config;
+        let digit = key_name(key)
+            .and_then(|name| name.strip_prefix("Key"))
+            .and_then(|digit| digit.parse::<u32>().ok());
+        match (key, digit) {
+            (_, Some(hp)) if hp > 0 => editor.brush = Cell::Brick(hp),
+            (KeyCode::O, _) => editor.brush = Cell::Obstacle,
+            (KeyCode::Up, _) => editor.adjust_ball_speed(config, BALL_SPEED_STEP),
+            (KeyCode::Down, _) => editor.adjust_ball_speed(config, -BALL_SPEED_STEP),
+            (KeyCode::Right, _) => editor.adjust_hearts(config, 1),
+            (KeyCode::Left, _) => editor.adjust_hearts(config, -1),
+            (KeyCode::PageUp, _) => editor.adjust_bar_width(config, BAR_WIDTH_STEP),
+            (KeyCode::PageDown, _) => editor.adjust_bar_width(config, -BAR_WIDTH_STEP),
+            (KeyCode::S, _) => self.save_level(),
+            (KeyCode::T, _) => self.playtest(),
+            (KeyCode::Escape, _) => self.leave_editor(),
+            _ => return false,
+        }
+        true
+    }
+
+    // Left button paints with the brush, right button clears
+    pub fn edit_cell(&mut self, x: f32, y: f32, button: MouseButton) {
+        let Some(editor) = &mut self.editor else {
+            return;
+        };
+        if let Some((row, column)) = editor.cell_at(&self.sim.config, x, y) {
+            match button {
+                MouseButton::Left => editor.paint(row, column),
+                MouseButton::Right => editor.erase(row, column),
+                _ => {}
+            }
+        }
+    }
+
+    pub fn save_level(&mut self) {
+        let Some(editor) = &self.editor else {
+            return;
+        };
+        let (text, color) = match editor.save(&self.sim.config) {
+            Ok(()) => (format!("Saved {}", editor.path.display()), Color::GREEN),
+            Err(e) => (format!("Not saved: {}", e), Color::RED),
+        };
+        self.editor_message(text, color);
+    }
+
+    // Play the level being edited through the normal game loop, on its own
+    pub fn playtest(&mut self) {
+        let Some(editor) = &self.editor else {
+            return;
+        };
+        match editor.playable(&self.sim.config) {
+            Ok(level) => {
+                let levels = std::mem::replace(&mut self.sim.config.levels, vec![level]);
+                self.campaign.get_or_insert(levels);
+                self.reset();
+            }
+            Err(e) => self.editor_message(format!("Can't play-test: {}", e), Color::RED),
+        }
+    }
+
+    pub fn return_to_editor(&mut self) {
+        self.sim.state = GameState::Editor;
+        self.animations.clear();
+    }
+
+    // Back to the real levels with a fresh run
+    pub fn leave_editor(&mut self) {
+        if let Some(levels) = self.campaign.take() {
+            self.sim.config.levels = levels;
+        }
+        self.reset();
+    }
+
+    fn editor_message(&mut self, text: String, color: Color) {
+        self.animations.clear();
+        let [cx, _] = self.screen_center();
+        self.add_animation(text, [cx, self.sim.config.height - 60.0], 2, 28.0, color);
+    }
+
+    fn draw_editor(&self, ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
+        let Some(editor) = &self.editor else {
+            return Ok(());
+        };
+        let config = &self.sim.config;
+
+        // The whole grid in one mesh, with the cell under the mouse outlined
+        let mouse = ctx.mouse.position();
+        let hovered = editor.cell_at(config, mouse.x, mouse.y);
+        let mut builder = graphics::MeshBuilder::new();
+        for row in 0..editor.rows() {
+            for column in 0..editor.columns() {
+                let area = editor.cell_area(config, row, column);
+                let rect = graphics::Rect::new(area.x, area.y, area.w, area.h);
+                match editor.cell(row, column) {
+                    Cell::Empty => builder.rectangle(
+                        graphics::DrawMode::stroke(1.0),
+                        rect,
+                        Color::new(0.3, 0.3, 0.3, 1.0),
+                    )?,
+                    cell => {
+                        builder.rectangle(graphics::DrawMode::fill(), rect, cell_color(cell))?
+                    }
+                };
+                if hovered == Some((row, column)) {
+                    builder.rectangle(graphics::DrawMode::stroke(3.0), rect, Color::YELLOW)?;
+                }
+            }
+        }
+        graphics::Mesh::from_data(ctx, builder.build()).draw(canvas, DrawParam::default());
+
+        let brush = match editor.brush {
+            Cell::Brick(hp) => format!("{} hit brick", hp),
+            _ => "Obstacle".to_string(),
+        };
+        let info = format!(
+            "Level Editor: {}\nBrush: {}\nBall speed: {:.0}\nBar width: {:.0}\nHearts: {}\nFile: {}",
+            editor.level.name,
+            brush,
+            editor.ball_speed(config),
+            editor.bar_width(config),
+            editor.hearts(config),
+            editor.path.display()
+        );
+        let top = config.height / 3.0 + 20.0;
+        Text::new(TextFragment::new(info).scale(24.0))
+            .draw(canvas, DrawParam::default().dest([20.0, top]));
+
+        let help = "Left click - Paint\nRight click - Erase\n1-9 - Brick hit points\nO - Obstacle\n\
+                    Up/Down - Ball speed\nLeft/Right - Hearts\nPageUp/PageDown - Bar width\n\
+                    S - Save\nT - Play-test (Esc returns here)\nEsc - Back to the game";
+        Text::new(TextFragment::new(help).scale(20.0)).draw(
+            canvas,
+            DrawParam::default().dest([config.width - 340.0, top]),
+        );
+        Ok(())
+    }
+
+    fn draw_animations(&self, ctx: &mut Context, canvas: &mut graphics::Canvas) {
+        for anim in &self.animations {
+            let text = Text::new(
+                TextFragment::new(&anim.text)
+                    .scale(anim.scale)
+                    .color(anim.color),
+            );
+            let dims = text.dimensions(ctx).unwrap();
+            text.draw(
+                canvas,
+                DrawParam::default().dest([
+                    anim.position[0] - dims.w / 2.0,
+                    anim.position[1] - dims.h / 2.0,
+                ]),
+            );
+        }
     }
 }
 
 impl Default for Bar {
     fn default() -> Self {
-        Self::new()
+        Self::new(&GameConfig::default())
     }
 }
 
 impl Ball {
-    pub fn new() -> Self {
-        let mut rng = rand::thread_rng();
+    pub fn new(config: &GameConfig, rng: &mut impl Rng) -> Self {
         Self {
-            x: rng.gen_range(BALL_SIZE..WIDTH - BALL_SIZE),
-            y: HEIGHT / 3.0,
+            x: rng.gen_range(config.ball_size..config.width - config.ball_size),
+            y: config.height / 3.0,
             dx: if rng.gen_bool(0.5) {
-                BALL_SPEED
+                config.ball_speed
             } else {
-                -BALL_SPEED
+                -config.ball_speed
             },
-            dy: BALL_SPEED,
+            dy: config.ball_speed,
+            size: config.ball_size,
         }
     }
 
-    pub fn update(&mut self) {
-        self.x += self.dx;
-        self.y += self.dy;
+    pub fn update(&mut self, dt: f32) {
+        self.x += self.dx * dt;
+        self.y += self.dy * dt;
+    }
+
+    pub fn aabb(&self) -> Aabb {
+        Aabb::new(self.x, self.y, self.size, self.size)
     }
 }
 
 impl Bar {
-    pub fn new() -> Self {
+    pub fn new(config: &GameConfig) -> Self {
+        Self {
+            x: (config.width - config.bar_width) / 2.0,
+            y: config.height - config.bar_height - BAR_MARGIN,
+            width: config.bar_width,
+            height: config.bar_height,
+            speed: config.bar_speed,
+            field_width: config.width,
+        }
+    }
+
+    // Player two's bar, the same distance from the top as player one's from the bottom
+    pub fn new_top(config: &GameConfig) -> Self {
         Self {
-            x: (WIDTH - BAR_WIDTH) / 2.0,
-            y: HEIGHT - BAR_HEIGHT - 10.0,
-            width: BAR_WIDTH,
+            y: BAR_MARGIN,
+            ..Self::new(config)
+        }
+    }
+
+    pub fn aabb(&self) -> Aabb {
+        Aabb::new(self.x, self.y, self.width, self.height)
+    }
+
+    // Keys first, then the stick, then the pointer
+    pub fn steer(&mut self, input: Input, config: &GameConfig) {
+        if input.left {
+            self.move_left(TICK_DT);
+        }
+        if input.right {
+            self.move_right(TICK_DT);
         }
+        // The stick only steers when no digital direction is held
+        if !input.left && !input.right && input.axis != 0 {
+            self.slide(input.axis_amount(), TICK_DT);
+        }
+        // The pointer only steers when nothing else does
+        if !input.is_steering()
+            && let Some(target) = input.target
+        {
+            let speed = config.pointer_speed * self.speed / config.bar_speed;
+            self.follow(target as f32, speed, TICK_DT);
+        }
+    }
+
+    // Where the ball is along the bar: -1 at the left edge, 0 in the middle, 1 at the right edge
+    pub fn hit_offset(&self, ball: &Ball) -> f32 {
+        let ball_center = ball.x + ball.size / 2.0;
+        let bar_center = self.x + self.width / 2.0;
+        let reach = (self.width + ball.size) / 2.0;
+        ((ball_center - bar_center) / reach).clamp(-1.0, 1.0)
     }
 
-    pub fn move_left(&mut self) {
-        self.x -= BAR_SPEED;
+    pub fn move_left(&mut self, dt: f32) {
+        self.x -= self.speed * dt;
         if self.x < 0.0 {
             self.x = 0.0;
         }
     }
 
-    pub fn move_right(&mut self) {
-        self.x += BAR_SPEED;
-        if self.x + self.width > WIDTH {
-            self.x = WIDTH - self.width;
+    pub fn move_right(&mut self, dt: f32) {
+        self.x += self.speed * dt;
+        if self.x + self.width > self.field_width {
+            self.x = self.field_width - self.width;
+        }
+    }
+
+    // Grow or shrink around the current centre, staying inside the field
+    pub fn set_width(&mut self, width: f32) {
+        let centre = self.x + self.width / 2.0;
+        self.width = width.min(self.field_width);
+        self.x = (centre - self.width / 2.0).clamp(0.0, self.field_width - self.width);
+    }
+
+    // Move at a fraction of full speed, negative to the left
+    pub fn slide(&mut self, amount: f32, dt: f32) {
+        if amount < 0.0 {
+            self.move_left(-amount * dt);
+        } else {
+            self.move_right(amount * dt);
+        }
+    }
+
+    // Head for `target` (the bar's centre x) at no more than `max_speed`,
+    // stopping on it rather than overshooting
+    pub fn follow(&mut self, target: f32, max_speed: f32, dt: f32) {
+        let offset = target - (self.x + self.width / 2.0);
+        let distance = offset.abs().min(max_speed * dt);
+        self.slide(offset.signum() * distance / (self.speed * dt), dt);
+    }
+}
+
+// Editor cells: tougher bricks are brighter, obstacles grey as in play
+fn cell_color(cell: Cell) -> Color {
+    match cell {
+        Cell::Brick(hp) => {
+            let shade = 0.4 + 0.6 * hp.min(9) as f32 / 9.0;
+            Color::new(shade, 0.5 * shade, 0.2, 1.0)
         }
+        _ => Color::new(0.5, 0.5, 0.5, 1.0),
+    }
+}
+
+fn power_up_color(power_type: PowerUpType) -> Color {
+    match power_type {
+        PowerUpType::WidthIncrease => Color::YELLOW,
+        PowerUpType::SpeedBoost => Color::RED,
+        PowerUpType::ExtraHeart => Color::GREEN,
+        PowerUpType::SlowBall => Color::CYAN,
+        PowerUpType::MultiBall => Color::MAGENTA,
     }
 }
 
 // Mock Context for testing without running the event loop
-pub fn create_game_ctx()
--> Result<(ggez::Context, ggez::event::EventLoop<()>), Box<dyn std::error::Error>> {
-    let mode = Conf::new().window_mode(WindowMode::default().dimensions(WIDTH, HEIGHT));
+pub fn create_game_ctx(
+    config: &GameConfig,
+) -> Result<(ggez::Context, ggez::event::EventLoop<()>), Box<dyn std::error::Error>> {
+    let mode =
+        Conf::new().window_mode(WindowMode::default().dimensions(config.width, config.height));
     let (ctx, event_loop) = ContextBuilder::new("bounce_shield", "🏐")
         .default_conf(mode)
         .build()?;
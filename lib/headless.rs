@@ -0,0 +1,58 @@
+use crate::autopilot::{Autopilot, Difficulty};
+use crate::game::{Game, GameState, Input};
+use crate::net::{NetError, Network};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Headless games still run in real time, so the countdown and snapshots go
+// out at the pace a windowed game sends them
+const FRAME: Duration = Duration::from_micros(16_667);
+// How long a host keeps sending the finished match for the client to see it
+const LINGER: Duration = Duration::from_secs(2);
+
+// Runs one network match without a window or sound, through the same
+// Game::frame as the windowed game: the host's bar is played by the
+// autopilot and the client leaves its bar where it is. Returns how the match
+// ended, e.g. "Player 1 wins", once the client has seen Game Over.
+pub fn run_headless(game: &mut Game) -> Result<String, NetError> {
+    let mut autopilot = Autopilot::new(Difficulty::Normal, game.sim.seed);
+    let mut joined = false;
+    let mut finished_at = None;
+    loop {
+        let live = if game.is_client() {
+            Input::default()
+        } else {
+            autopilot.next_input(&game.sim)
+        };
+        game.frame(FRAME, (live, Input::default()));
+
+        match &game.net {
+            Some(Network::Disconnected) => return Err(NetError::Disconnected),
+            Some(Network::Client(_)) if game.sim.state == GameState::GameOver => {
+                return Ok(result(game));
+            }
+            Some(Network::Host(_)) => {
+                // Left before the end of the match, or stayed to see it
+                let waiting = game.waiting_for_peer();
+                joined |= !waiting;
+                if game.sim.state == GameState::GameOver {
+                    let finished_at = *finished_at.get_or_insert_with(Instant::now);
+                    if waiting || finished_at.elapsed() >= LINGER {
+                        return Ok(result(game));
+                    }
+                } else if joined && waiting {
+                    return Err(NetError::Disconnected);
+                }
+            }
+            _ => {}
+        }
+        thread::sleep(FRAME);
+    }
+}
+
+fn result(game: &Game) -> String {
+    match game.sim.winner() {
+        Some(winner) => format!("{} wins", winner.label()),
+        None => "Game Over".to_string(),
+    }
+}
@@ -0,0 +1,65 @@
+use crate::data_file::{load_or_default, write_atomically};
+use std::io;
+use std::path::Path;
+
+pub const HIGH_SCORES_FILE: &str = "high_scores.txt";
+pub const MAX_HIGH_SCORES: usize = 10;
+const HIGH_SCORES_HEADER: &str = "bounce_shield high scores v1";
+
+// Best scores across sessions, highest first
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HighScores {
+    pub scores: Vec<usize>,
+}
+
+impl HighScores {
+    pub fn best(&self) -> usize {
+        self.scores.first().copied().unwrap_or(0)
+    }
+
+    // Returns true if the score made it onto the table
+    pub fn record(&mut self, score: usize) -> bool {
+        if score == 0 {
+            return false;
+        }
+        let rank = self.scores.partition_point(|&s| s >= score);
+        if rank >= MAX_HIGH_SCORES {
+            return false;
+        }
+        self.scores.insert(rank, score);
+        self.scores.truncate(MAX_HIGH_SCORES);
+        true
+    }
+
+    // None if the text isn't a high score file of a version we understand
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut lines = text.lines();
+        if lines.next()?.trim() != HIGH_SCORES_HEADER {
+            return None;
+        }
+        let mut scores = lines
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| line.parse().ok())
+            .collect::<Option<Vec<usize>>>()?;
+        scores.sort_unstable_by(|a, b| b.cmp(a));
+        scores.truncate(MAX_HIGH_SCORES);
+        Some(Self { scores })
+    }
+
+    pub fn to_file_string(&self) -> String {
+        let mut text = format!("{}\n", HIGH_SCORES_HEADER);
+        for score in &self.scores {
+            text.push_str(&format!("{}\n", score));
+        }
+        text
+    }
+
+    pub fn load(path: &Path) -> Self {
+        load_or_default(path, "high score", Self::parse)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        write_atomically(path, self.to_file_string())
+    }
+}
@@ -0,0 +1,322 @@
+use crate::bricks::{Brick, grid_cell};
+use crate::config::GameConfig;
+use crate::physics::Aabb;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const LEVELS_DIR: &str = "levels";
+pub const LEVEL_EXTENSION: &str = "txt";
+
+// One square of a level layout
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cell {
+    Empty,
+    Brick(u32), // hit points
+    Obstacle,   // never breaks
+}
+
+// A stage read from a level file. Settings left out of the file keep the
+// values from GameConfig.
+//
+//     # comment
+//     name = First Steps
+//     ball_speed = 300
+//     bar_width = 150
+//     hearts = 3
+//     layout:
+//     ..1111..
+//     .2#22#2.
+//
+// In the layout `1`-`9` is a brick with that many hit points, `#` an
+// obstacle and `.` or a space an empty cell. The grid is stretched over the
+// same area as the generated brick rows.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Level {
+    pub name: String,
+    pub ball_speed: Option<f32>,
+    pub bar_width: Option<f32>,
+    pub hearts: Option<usize>,
+    pub layout: Vec<Vec<Cell>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+#[derive(Debug)]
+pub enum LevelError {
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, ParseError),
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelError::Io(path, e) => write!(f, "failed to read {}: {}", path.display(), e),
+            LevelError::Parse(path, e) => write!(f, "{}:{}: {}", path.display(), e.line, e.message),
+        }
+    }
+}
+
+impl std::error::Error for LevelError {}
+
+impl Level {
+    pub fn parse(text: &str, config: &GameConfig) -> Result<Self, ParseError> {
+        let mut level = Level {
+            name: String::new(),
+            ball_speed: None,
+            bar_width: None,
+            hearts: None,
+            layout: Vec::new(),
+        };
+        let mut layout_line = None;
+        let mut blank_row = None;
+        for (index, raw) in text.lines().enumerate() {
+            let line = index + 1;
+            let error = |message: String| Err(ParseError { line, message });
+            if layout_line.is_some() {
+                let row = raw.trim_end();
+                // Blank lines may only end the file; inside the layout they
+                // would silently shift every row below
+                if row.is_empty() {
+                    blank_row.get_or_insert(line);
+                    continue;
+                }
+                if let Some(line) = blank_row {
+                    return Err(ParseError {
+                        line,
+                        message: "blank row in layout, use '.' for empty cells".to_string(),
+                    });
+                }
+                level.layout.push(parse_row(row, line)?);
+                continue;
+            }
+
+            let setting = raw.split('#').next().unwrap_or("").trim();
+            if setting.is_empty() {
+                continue;
+            }
+            if setting == "layout:" {
+                layout_line = Some(line);
+                continue;
+            }
+            let Some((key, value)) = setting.split_once('=') else {
+                return error(format!(
+                    "expected 'key = value' or 'layout:', got '{}'",
+                    setting
+                ));
+            };
+            let (key, value) = (key.trim(), value.trim());
+            match key {
+                "name" => level.name = value.to_string(),
+                "ball_speed" => {
+                    let speed = parse_number(value, line)?;
+                    if speed <= 0.0 {
+                        return error(format!("ball_speed must be positive, got {}", value));
+                    }
+                    level.ball_speed = Some(speed);
+                }
+                "bar_width" => {
+                    let width = parse_number(value, line)?;
+                    if width <= 0.0 || width > config.width {
+                        return error(format!(
+                            "bar_width must be between 0 and the field width ({}), got {}",
+                            config.width, value
+                        ));
+                    }
+                    level.bar_width = Some(width);
+                }
+                "hearts" => match value.parse::<usize>() {
+                    Ok(hearts) if hearts > 0 => level.hearts = Some(hearts),
+                    _ => {
+                        return error(format!(
+                            "hearts must be a whole number above 0, got '{}'",
+                            value
+                        ));
+                    }
+                },
+                _ => return error(format!("unknown setting '{}'", key)),
+            }
+        }
+
+        let Some(layout_line) = layout_line else {
+            return Err(ParseError {
+                line: text.lines().count().max(1),
+                message: "missing 'layout:' section".to_string(),
+            });
+        };
+        let no_bricks = !level
+            .layout
+            .iter()
+            .flatten()
+            .any(|cell| matches!(cell, Cell::Brick(_)));
+        if no_bricks {
+            return Err(ParseError {
+                line: layout_line,
+                message: "layout has no bricks to clear".to_string(),
+            });
+        }
+        let cell = grid_cell(config, level.layout.len(), level.columns(), 0, 0);
+        if cell.w < 1.0 || cell.h < 1.0 {
+            return Err(ParseError {
+                line: layout_line,
+                message: format!(
+                    "a layout of {} rows and {} columns doesn't fit in the field",
+                    level.layout.len(),
+                    level.columns()
+                ),
+            });
+        }
+        Ok(level)
+    }
+
+    pub fn load(path: &Path, config: &GameConfig) -> Result<Self, LevelError> {
+        let text = fs::read_to_string(path).map_err(|e| LevelError::Io(path.to_path_buf(), e))?;
+        let mut level =
+            Self::parse(&text, config).map_err(|e| LevelError::Parse(path.to_path_buf(), e))?;
+        if level.name.is_empty() {
+            level.name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+        }
+        Ok(level)
+    }
+
+    // The file format `parse` reads back
+    pub fn to_file_string(&self) -> String {
+        let mut text = String::new();
+        if !self.name.is_empty() {
+            text.push_str(&format!("name = {}\n", self.name));
+        }
+        if let Some(speed) = self.ball_speed {
+            text.push_str(&format!("ball_speed = {}\n", speed));
+        }
+        if let Some(width) = self.bar_width {
+            text.push_str(&format!("bar_width = {}\n", width));
+        }
+        if let Some(hearts) = self.hearts {
+            text.push_str(&format!("hearts = {}\n", hearts));
+        }
+        text.push_str("layout:\n");
+        for row in &self.layout {
+            for cell in row {
+                text.push(match cell {
+                    Cell::Empty => '.',
+                    Cell::Brick(hp) => char::from_digit(*hp, 10).unwrap_or('9'),
+                    Cell::Obstacle => '#',
+                });
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    pub fn columns(&self) -> usize {
+        self.layout.iter().map(Vec::len).max().unwrap_or(0)
+    }
+
+    // `bonus` extra hit points per brick, for playing the levels again after the last one
+    pub fn bricks(&self, config: &GameConfig, bonus: u32) -> Vec<Brick> {
+        self.cells(config)
+            .filter_map(|(cell, area)| match cell {
+                Cell::Brick(hp) => Some(Brick::new(area.x, area.y, area.w, area.h, hp + bonus)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn obstacles(&self, config: &GameConfig) -> Vec<Aabb> {
+        self.cells(config)
+            .filter(|(cell, _)| *cell == Cell::Obstacle)
+            .map(|(_, area)| area)
+            .collect()
+    }
+
+    fn cells(&self, config: &GameConfig) -> impl Iterator<Item = (Cell, Aabb)> {
+        let (rows, columns) = (self.layout.len(), self.columns());
+        self.layout
+            .iter()
+            .enumerate()
+            .flat_map(move |(row, cells)| {
+                cells.iter().enumerate().map(move |(column, &cell)| {
+                    (cell, grid_cell(config, rows, columns, row, column))
+                })
+            })
+    }
+
+    // Everything about the level that changes how a run plays, for replay fingerprints
+    pub fn gameplay_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&self.ball_speed.unwrap_or(0.0).to_le_bytes());
+        bytes.extend_from_slice(&self.bar_width.unwrap_or(0.0).to_le_bytes());
+        bytes.extend_from_slice(&(self.hearts.unwrap_or(0) as u64).to_le_bytes());
+        for row in &self.layout {
+            for cell in row {
+                bytes.push(match cell {
+                    Cell::Empty => 0,
+                    Cell::Brick(hp) => *hp as u8,
+                    Cell::Obstacle => 0xff,
+                });
+            }
+            bytes.push(b'\n');
+        }
+        bytes
+    }
+}
+
+fn parse_row(row: &str, line: usize) -> Result<Vec<Cell>, ParseError> {
+    row.chars()
+        .enumerate()
+        .map(|(column, c)| match c {
+            '.' | ' ' => Ok(Cell::Empty),
+            '#' => Ok(Cell::Obstacle),
+            '1'..='9' => Ok(Cell::Brick(c.to_digit(10).unwrap())),
+            _ => Err(ParseError {
+                line,
+                message: format!("unexpected '{}' in layout at column {}", c, column + 1),
+            }),
+        })
+        .collect()
+}
+
+fn parse_number(value: &str, line: usize) -> Result<f32, ParseError> {
+    value
+        .parse::<f32>()
+        .ok()
+        .filter(|n| n.is_finite())
+        .ok_or_else(|| ParseError {
+            line,
+            message: format!("expected a number, got '{}'", value),
+        })
+}
+
+// Every level file in `dir`, in file name order. No directory means no levels.
+pub fn load_levels(dir: &Path, config: &GameConfig) -> Result<Vec<Level>, LevelError> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(LevelError::Io(dir.to_path_buf(), e)),
+    };
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|e| LevelError::Io(dir.to_path_buf(), e))?
+            .path();
+        if path.extension().is_some_and(|ext| ext == LEVEL_EXTENSION) {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    paths.iter().map(|path| Level::load(path, config)).collect()
+}
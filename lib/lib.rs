@@ -1,2 +1,43 @@
+mod audio_settings;
+mod autopilot;
+mod bindings;
+mod bricks;
+mod camera;
+mod config;
+mod data_file;
+mod display_settings;
+mod editor;
+pub mod env;
 mod game;
+mod headless;
+mod high_scores;
+mod levels;
+mod menu;
+mod net;
+mod options;
+mod particles;
+mod physics;
+mod power_ups;
+mod replay;
+mod sound;
+pub use audio_settings::*;
+pub use autopilot::*;
+pub use bindings::*;
+pub use bricks::*;
+pub use camera::*;
+pub use config::*;
+pub use data_file::*;
+pub use display_settings::*;
+pub use editor::*;
 pub use game::*;
+pub use headless::*;
+pub use high_scores::*;
+pub use levels::*;
+pub use menu::*;
+pub use net::*;
+pub use options::*;
+pub use particles::*;
+pub use physics::*;
+pub use power_ups::*;
+pub use replay::*;
+pub use sound::*;
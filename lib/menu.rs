@@ -0,0 +1,171 @@
+use crate::audio_settings::Channel;
+use std::time::Duration;
+
+// Left alone this long, the title screen starts the demo
+pub const ATTRACT_DELAY: Duration = Duration::from_secs(20);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuItem {
+    Play,
+    Modes,
+    Settings,
+    HighScores,
+    Quit,
+    OnePlayer,
+    TwoPlayers,
+    Demo,
+    ScreenShake,
+    Sound,
+    Volume(Channel), // changed with left and right rather than picked
+    RebindKeys,
+    Back,
+}
+
+impl MenuItem {
+    pub fn label(self) -> &'static str {
+        match self {
+            MenuItem::Play => "Play",
+            MenuItem::Modes => "Modes",
+            MenuItem::Settings => "Settings",
+            MenuItem::HighScores => "High Scores",
+            MenuItem::Quit => "Quit",
+            MenuItem::OnePlayer => "One Player",
+            MenuItem::TwoPlayers => "Two Players",
+            MenuItem::Demo => "Demo",
+            MenuItem::ScreenShake => "Screen Shake",
+            MenuItem::Sound => "Sound",
+            MenuItem::Volume(Channel::Master) => "Master Volume",
+            MenuItem::Volume(Channel::Music) => "Music Volume",
+            MenuItem::Volume(Channel::Effects) => "Effects Volume",
+            MenuItem::RebindKeys => "Rebind Keys",
+            MenuItem::Back => "Back",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuPage {
+    Main,
+    Modes,
+    Settings,
+    HighScores,
+}
+
+impl MenuPage {
+    pub fn items(self) -> &'static [MenuItem] {
+        match self {
+            MenuPage::Main => &[
+                MenuItem::Play,
+                MenuItem::Modes,
+                MenuItem::Settings,
+                MenuItem::HighScores,
+                MenuItem::Quit,
+            ],
+            MenuPage::Modes => &[
+                MenuItem::OnePlayer,
+                MenuItem::TwoPlayers,
+                MenuItem::Demo,
+                MenuItem::Back,
+            ],
+            MenuPage::Settings => &[
+                MenuItem::ScreenShake,
+                MenuItem::Sound,
+                MenuItem::Volume(Channel::Master),
+                MenuItem::Volume(Channel::Music),
+                MenuItem::Volume(Channel::Effects),
+                MenuItem::RebindKeys,
+                MenuItem::Back,
+            ],
+            MenuPage::HighScores => &[MenuItem::Back],
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            MenuPage::Main => "Bounce Shield",
+            MenuPage::Modes => "Modes",
+            MenuPage::Settings => "Settings",
+            MenuPage::HighScores => "High Scores",
+        }
+    }
+
+    // The main menu entry that opens this page
+    fn opened_by(self) -> MenuItem {
+        match self {
+            MenuPage::Main => MenuItem::Play,
+            MenuPage::Modes => MenuItem::Modes,
+            MenuPage::Settings => MenuItem::Settings,
+            MenuPage::HighScores => MenuItem::HighScores,
+        }
+    }
+}
+
+// Which page of the title screen is showing and what is highlighted on it.
+// Moving between pages happens here; everything else an entry does is up to
+// the game.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Menu {
+    pub page: MenuPage,
+    pub selected: usize,
+}
+
+impl Default for Menu {
+    fn default() -> Self {
+        Self {
+            page: MenuPage::Main,
+            selected: 0,
+        }
+    }
+}
+
+impl Menu {
+    pub fn selected_item(&self) -> MenuItem {
+        self.page.items()[self.selected]
+    }
+
+    // Up and down wrap around the ends
+    pub fn move_up(&mut self) {
+        let count = self.page.items().len();
+        self.selected = (self.selected + count - 1) % count;
+    }
+
+    pub fn move_down(&mut self) {
+        self.selected = (self.selected + 1) % self.page.items().len();
+    }
+
+    // Returns the chosen entry once any page change it causes is done
+    pub fn confirm(&mut self) -> MenuItem {
+        let item = self.selected_item();
+        match item {
+            MenuItem::Modes => self.open(MenuPage::Modes),
+            MenuItem::Settings => self.open(MenuPage::Settings),
+            MenuItem::HighScores => self.open(MenuPage::HighScores),
+            MenuItem::Back => {
+                self.back();
+            }
+            _ => {}
+        }
+        item
+    }
+
+    fn open(&mut self, page: MenuPage) {
+        self.page = page;
+        self.selected = 0;
+    }
+
+    // Back to the main page with the entry that led away highlighted; false
+    // when already there
+    pub fn back(&mut self) -> bool {
+        if self.page == MenuPage::Main {
+            return false;
+        }
+        let from = self.page.opened_by();
+        self.page = MenuPage::Main;
+        self.selected = MenuPage::Main
+            .items()
+            .iter()
+            .position(|&item| item == from)
+            .unwrap_or(0);
+        true
+    }
+}
@@ -0,0 +1,775 @@
+use crate::config::GameConfig;
+use crate::game::{Ball, GameState, Input, Player, SimEvent, Simulation, Surface};
+use crate::power_ups::PowerUpType;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_PORT: u16 = 7777;
+// Bumped whenever a message changes; both sides must match
+pub const PROTOCOL_VERSION: u8 = 3;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+// A new connection that hasn't said Hello by then is dropped to free the slot
+pub const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+// Both sides send every frame, so this long without a word means the other
+// side is gone even if the connection was never closed
+pub const IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+// The most a closing connection may hold the game up to deliver its last message
+const CLOSE_TIMEOUT: Duration = Duration::from_millis(250);
+// Frames bigger than this are a broken or hostile peer, not a snapshot
+pub const MAX_FRAME: usize = 64 * 1024;
+// A peer this far behind on reading is treated as gone
+const MAX_BACKLOG: usize = 1024 * 1024;
+
+#[derive(Debug)]
+pub enum NetError {
+    Io(io::Error),
+    Protocol(String),
+    Rejected(String),
+    Disconnected,
+    TimedOut,
+}
+
+impl fmt::Display for NetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetError::Io(e) => write!(f, "network error: {}", e),
+            NetError::Protocol(reason) => write!(f, "bad message from the other side: {}", reason),
+            NetError::Rejected(reason) => write!(f, "the host turned us away: {}", reason),
+            NetError::Disconnected => write!(f, "the other side disconnected"),
+            NetError::TimedOut => write!(f, "the other side stopped responding"),
+        }
+    }
+}
+
+impl std::error::Error for NetError {}
+
+impl From<io::Error> for NetError {
+    fn from(e: io::Error) -> Self {
+        NetError::Io(e)
+    }
+}
+
+// Everything the client needs to draw a frame of the host's game, plus what
+// happened since the last one so it can play the same sounds and messages
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    pub tick: u64,
+    pub state: GameState,
+    pub countdown: i32,
+    pub balls: Vec<[f32; 2]>,
+    pub bar: [f32; 2],     // x and width
+    pub top_bar: [f32; 2], // x and width
+    pub score: usize,
+    pub hearts: usize,
+    pub score_two: usize,
+    pub hearts_two: usize,
+    pub events: Vec<SimEvent>,
+}
+
+impl Snapshot {
+    pub fn capture(sim: &Simulation, countdown: i32, events: Vec<SimEvent>) -> Self {
+        let player_two = sim.player_two.as_ref();
+        Self {
+            tick: sim.tick,
+            state: sim.state,
+            countdown,
+            balls: sim.balls.iter().map(|ball| [ball.x, ball.y]).collect(),
+            bar: [sim.bar.x, sim.bar.width],
+            top_bar: player_two.map_or([0.0, 0.0], |p| [p.bar.x, p.bar.width]),
+            score: sim.score,
+            hearts: sim.hearts,
+            score_two: player_two.map_or(0, |p| p.score),
+            hearts_two: player_two.map_or(0, |p| p.hearts),
+            events,
+        }
+    }
+
+    // Copy the host's state into the client's simulation, which is only
+    // used for drawing and never stepped
+    pub fn apply(&self, sim: &mut Simulation) {
+        sim.tick = self.tick;
+        sim.state = self.state;
+        let size = sim.config.ball_size;
+        sim.balls = self
+            .balls
+            .iter()
+            .map(|&[x, y]| Ball {
+                x,
+                y,
+                dx: 0.0,
+                dy: 0.0,
+                size,
+            })
+            .collect();
+        [sim.bar.x, sim.bar.width] = self.bar;
+        sim.score = self.score;
+        sim.hearts = self.hearts;
+        if let Some(player_two) = &mut sim.player_two {
+            [player_two.bar.x, player_two.bar.width] = self.top_bar;
+            player_two.score = self.score_two;
+            player_two.hearts = self.hearts_two;
+        }
+    }
+
+    // Positions part way from `self` to `next`; anything that can't be
+    // blended, like a ball appearing, comes from `next`
+    pub fn lerp(&self, next: &Snapshot, t: f32) -> Snapshot {
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+        let mut blended = next.clone();
+        if self.balls.len() == next.balls.len() {
+            for (ball, (from, to)) in blended
+                .balls
+                .iter_mut()
+                .zip(self.balls.iter().zip(&next.balls))
+            {
+                *ball = [mix(from[0], to[0]), mix(from[1], to[1])];
+            }
+        }
+        blended.bar[0] = mix(self.bar[0], next.bar[0]);
+        blended.top_bar[0] = mix(self.top_bar[0], next.top_bar[0]);
+        blended
+    }
+}
+
+// What goes over the wire, one message per frame
+#[derive(Clone, Debug, PartialEq)]
+pub enum Message {
+    Hello { version: u8, config_hash: u64 },
+    Welcome,
+    Reject(String),
+    Input(Input),
+    Snapshot(Snapshot),
+}
+
+impl Message {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        match self {
+            Message::Hello {
+                version,
+                config_hash,
+            } => {
+                bytes.push(0);
+                bytes.push(*version);
+                bytes.extend_from_slice(&config_hash.to_le_bytes());
+            }
+            Message::Welcome => bytes.push(1),
+            Message::Reject(reason) => {
+                bytes.push(2);
+                bytes.extend_from_slice(reason.as_bytes());
+            }
+            Message::Input(input) => {
+                bytes.push(3);
+                input.write_bytes(&mut bytes);
+            }
+            Message::Snapshot(snapshot) => {
+                bytes.push(4);
+                write_snapshot(&mut bytes, snapshot);
+            }
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, NetError> {
+        let mut rest = bytes;
+        let [tag] = take(&mut rest)?;
+        let message = match tag {
+            0 => {
+                let [version] = take(&mut rest)?;
+                let config_hash = u64::from_le_bytes(take(&mut rest)?);
+                Message::Hello {
+                    version,
+                    config_hash,
+                }
+            }
+            1 => Message::Welcome,
+            2 => {
+                let reason = String::from_utf8_lossy(rest).into_owned();
+                rest = &[];
+                Message::Reject(reason)
+            }
+            3 => Message::Input(
+                Input::read_bytes(&mut rest)
+                    .ok_or_else(|| protocol("message is cut short".to_string()))?,
+            ),
+            4 => Message::Snapshot(read_snapshot(&mut rest)?),
+            _ => return Err(protocol(format!("unknown message type {}", tag))),
+        };
+        if !rest.is_empty() {
+            return Err(protocol("trailing bytes after message".to_string()));
+        }
+        Ok(message)
+    }
+}
+
+fn protocol(reason: String) -> NetError {
+    NetError::Protocol(reason)
+}
+
+// Split the next N bytes off the front of `rest`
+fn take<const N: usize>(rest: &mut &[u8]) -> Result<[u8; N], NetError> {
+    let Some((head, tail)) = rest.split_first_chunk::<N>() else {
+        return Err(protocol("message is cut short".to_string()));
+    };
+    *rest = tail;
+    Ok(*head)
+}
+
+fn take_u32(rest: &mut &[u8]) -> Result<usize, NetError> {
+    Ok(u32::from_le_bytes(take(rest)?) as usize)
+}
+
+fn take_f32(rest: &mut &[u8]) -> Result<f32, NetError> {
+    Ok(f32::from_le_bytes(take(rest)?))
+}
+
+fn state_code(state: GameState) -> u8 {
+    match state {
+        GameState::Countdown => 0,
+        GameState::Playing => 1,
+        GameState::Paused => 2,
+        GameState::GameOver => 3,
+        GameState::Editor => 4,
+        GameState::MainMenu => 5,
+    }
+}
+
+fn state_from_code(code: u8) -> Result<GameState, NetError> {
+    Ok(match code {
+        0 => GameState::Countdown,
+        1 => GameState::Playing,
+        2 => GameState::Paused,
+        3 => GameState::GameOver,
+        4 => GameState::Editor,
+        5 => GameState::MainMenu,
+        _ => return Err(protocol(format!("unknown game state {}", code))),
+    })
+}
+
+// Events travel as a tag, a small value and a count, followed by the
+// position for the ones that happen somewhere
+fn write_event(bytes: &mut Vec<u8>, event: SimEvent) {
+    let power_up = |power_type| {
+        PowerUpType::ALL
+            .iter()
+            .position(|&t| t == power_type)
+            .unwrap_or(0) as u8
+    };
+    let (tag, small, count, position): (u8, u8, usize, &[f32]) = match event {
+        SimEvent::BarHit => (0, 0, 0, &[]),
+        SimEvent::NewHighScore(score) => (1, 0, score, &[]),
+        SimEvent::ExtraHeart => (2, 0, 0, &[]),
+        SimEvent::HeartLost { player, remaining } => {
+            (3, (player == Player::Two) as u8, remaining, &[])
+        }
+        SimEvent::GameOver => (4, 0, 0, &[]),
+        SimEvent::PowerUpCollected(power_type) => (5, power_up(power_type), 0, &[]),
+        SimEvent::PowerUpExpired(power_type) => (6, power_up(power_type), 0, &[]),
+        SimEvent::BrickHit => (7, 0, 0, &[]),
+        SimEvent::BrickDestroyed { score } => (8, 0, score, &[]),
+        SimEvent::StageCleared { next_stage } => (9, 0, next_stage, &[]),
+        SimEvent::Impact {
+            x,
+            y,
+            normal: [nx, ny],
+            surface,
+        } => (10, surface as u8, 0, &[x, y, nx, ny]),
+        SimEvent::BallLost { x, y } => (11, 0, 0, &[x, y]),
+    };
+    bytes.push(tag);
+    bytes.push(small);
+    bytes.extend_from_slice(&(count as u32).to_le_bytes());
+    for value in position {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+fn read_event(rest: &mut &[u8]) -> Result<SimEvent, NetError> {
+    let [tag, small] = take(rest)?;
+    let count = take_u32(rest)?;
+    let power_up = || {
+        PowerUpType::ALL
+            .get(small as usize)
+            .copied()
+            .ok_or_else(|| protocol(format!("unknown power-up {}", small)))
+    };
+    Ok(match tag {
+        0 => SimEvent::BarHit,
+        1 => SimEvent::NewHighScore(count),
+        2 => SimEvent::ExtraHeart,
+        3 => SimEvent::HeartLost {
+            player: if small == 0 { Player::One } else { Player::Two },
+            remaining: count,
+        },
+        4 => SimEvent::GameOver,
+        5 => SimEvent::PowerUpCollected(power_up()?),
+        6 => SimEvent::PowerUpExpired(power_up()?),
+        7 => SimEvent::BrickHit,
+        8 => SimEvent::BrickDestroyed { score: count },
+        9 => SimEvent::StageCleared { next_stage: count },
+        10 => SimEvent::Impact {
+            x: take_f32(rest)?,
+            y: take_f32(rest)?,
+            normal: [take_f32(rest)?, take_f32(rest)?],
+            surface: Surface::ALL
+                .get(small as usize)
+                .copied()
+                .ok_or_else(|| protocol(format!("unknown surface {}", small)))?,
+        },
+        11 => SimEvent::BallLost {
+            x: take_f32(rest)?,
+            y: take_f32(rest)?,
+        },
+        _ => return Err(protocol(format!("unknown event {}", tag))),
+    })
+}
+
+fn write_snapshot(bytes: &mut Vec<u8>, snapshot: &Snapshot) {
+    bytes.extend_from_slice(&snapshot.tick.to_le_bytes());
+    bytes.push(state_code(snapshot.state));
+    bytes.extend_from_slice(&snapshot.countdown.to_le_bytes());
+    for value in [snapshot.bar, snapshot.top_bar].iter().flatten() {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    for count in [
+        snapshot.score,
+        snapshot.hearts,
+        snapshot.score_two,
+        snapshot.hearts_two,
+        snapshot.balls.len(),
+        snapshot.events.len(),
+    ] {
+        bytes.extend_from_slice(&(count as u32).to_le_bytes());
+    }
+    for value in snapshot.balls.iter().flatten() {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    for &event in &snapshot.events {
+        write_event(bytes, event);
+    }
+}
+
+fn read_snapshot(rest: &mut &[u8]) -> Result<Snapshot, NetError> {
+    let tick = u64::from_le_bytes(take(rest)?);
+    let [state] = take(rest)?;
+    let countdown = i32::from_le_bytes(take(rest)?);
+    let bar = [take_f32(rest)?, take_f32(rest)?];
+    let top_bar = [take_f32(rest)?, take_f32(rest)?];
+    let [
+        score,
+        hearts,
+        score_two,
+        hearts_two,
+        ball_count,
+        event_count,
+    ] = [
+        take_u32(rest)?,
+        take_u32(rest)?,
+        take_u32(rest)?,
+        take_u32(rest)?,
+        take_u32(rest)?,
+        take_u32(rest)?,
+    ];
+    if ball_count * 8 + event_count * 6 > rest.len() {
+        return Err(protocol("snapshot is cut short".to_string()));
+    }
+    let mut balls = Vec::with_capacity(ball_count);
+    for _ in 0..ball_count {
+        balls.push([take_f32(rest)?, take_f32(rest)?]);
+    }
+    let mut events = Vec::with_capacity(event_count);
+    for _ in 0..event_count {
+        events.push(read_event(rest)?);
+    }
+    Ok(Snapshot {
+        tick,
+        state: state_from_code(state)?,
+        countdown,
+        balls,
+        bar,
+        top_bar,
+        score,
+        hearts,
+        score_two,
+        hearts_two,
+        events,
+    })
+}
+
+// Length-prefixed messages over a non-blocking TCP stream. Nothing here
+// ever waits: reads take what has arrived and writes queue what the socket
+// won't take yet.
+pub struct Connection {
+    stream: TcpStream,
+    incoming: Vec<u8>,
+    outgoing: Vec<u8>,
+    last_received: Instant,
+}
+
+impl Connection {
+    pub fn new(stream: TcpStream) -> Result<Self, NetError> {
+        stream.set_nonblocking(true)?;
+        stream.set_nodelay(true)?;
+        Ok(Self {
+            stream,
+            incoming: Vec::new(),
+            outgoing: Vec::new(),
+            last_received: Instant::now(),
+        })
+    }
+
+    pub fn send(&mut self, message: &Message) -> Result<(), NetError> {
+        let bytes = message.to_bytes();
+        self.outgoing
+            .extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        self.outgoing.extend_from_slice(&bytes);
+        if self.outgoing.len() > MAX_BACKLOG {
+            return Err(NetError::Disconnected);
+        }
+        self.flush()
+    }
+
+    fn flush(&mut self) -> Result<(), NetError> {
+        while !self.outgoing.is_empty() {
+            match self.stream.write(&self.outgoing) {
+                Ok(0) => return Err(NetError::Disconnected),
+                Ok(n) => {
+                    self.outgoing.drain(..n);
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+
+    // Every complete message that has arrived so far, reading at most
+    // MAX_FRAME bytes so a peer sending faster than we play can't hold up the
+    // frame; the rest waits for the next call. Messages sent just before the
+    // other side hung up, like a rejection, still come through.
+    pub fn receive(&mut self) -> Result<Vec<Message>, NetError> {
+        let mut buffer = [0; 4096];
+        let mut read = 0;
+        let mut closed = false;
+        while read < MAX_FRAME {
+            match self.stream.read(&mut buffer) {
+                Ok(0) => {
+                    closed = true;
+                    break;
+                }
+                Ok(n) => {
+                    self.incoming.extend_from_slice(&buffer[..n]);
+                    self.last_received = Instant::now();
+                    read += n;
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        let mut messages = Vec::new();
+        let mut start = 0;
+        while let Some(header) = self.incoming.get(start..start + 4) {
+            let length = u32::from_le_bytes(header.try_into().unwrap()) as usize;
+            if length > MAX_FRAME {
+                return Err(protocol(format!("{} byte message is too big", length)));
+            }
+            let Some(frame) = self.incoming.get(start + 4..start + 4 + length) else {
+                break;
+            };
+            messages.push(Message::from_bytes(frame)?);
+            start += 4 + length;
+        }
+        self.incoming.drain(..start);
+        if closed && messages.is_empty() {
+            return Err(NetError::Disconnected);
+        }
+        self.flush()?;
+        Ok(messages)
+    }
+
+    // How long since anything arrived, or since connecting if nothing has
+    pub fn idle_for(&self) -> Duration {
+        self.last_received.elapsed()
+    }
+
+    // Deliver what is still queued, like a rejection, then hang up. Reading
+    // until the other side hangs up too keeps input it sent meanwhile from
+    // resetting the connection before the last message gets there.
+    pub fn close(mut self) {
+        let deadline = Instant::now() + CLOSE_TIMEOUT;
+        let _ = self.stream.set_nonblocking(false);
+        let _ = self.stream.set_write_timeout(Some(CLOSE_TIMEOUT));
+        let _ = self.stream.set_read_timeout(Some(CLOSE_TIMEOUT));
+        if self.stream.write_all(&self.outgoing).is_ok()
+            && self.stream.shutdown(Shutdown::Write).is_ok()
+        {
+            let mut buffer = [0; 4096];
+            while Instant::now() < deadline
+                && matches!(self.stream.read(&mut buffer), Ok(n) if n > 0)
+            {}
+        }
+    }
+}
+
+// Shows the client's view one snapshot behind the host: the picture moves
+// from the previous snapshot to the latest over the time it took the latest
+// to arrive, so steady updates look smooth rather than stepping every frame
+#[derive(Default)]
+pub struct Interpolator {
+    previous: Option<(Instant, Snapshot)>,
+    latest: Option<(Instant, Snapshot)>,
+}
+
+impl Interpolator {
+    pub fn push(&mut self, now: Instant, snapshot: Snapshot) {
+        self.previous = self.latest.replace((now, snapshot));
+    }
+
+    pub fn sample(&self, now: Instant) -> Option<Snapshot> {
+        let (latest_at, latest) = self.latest.as_ref()?;
+        let Some((previous_at, previous)) = &self.previous else {
+            return Some(latest.clone());
+        };
+        let interval = latest_at.duration_since(*previous_at).as_secs_f32();
+        let t = if interval > 0.0 {
+            (now.duration_since(*latest_at).as_secs_f32() / interval).min(1.0)
+        } else {
+            1.0
+        };
+        Some(previous.lerp(latest, t))
+    }
+}
+
+// The host runs the real simulation with player one on its own keys and
+// player two's input coming from whoever has joined
+pub struct HostSession {
+    listener: TcpListener,
+    config_hash: u64,
+    peer: Option<Connection>,
+    accepted_at: Instant,
+    welcomed: bool,
+    remote_input: Input,
+    pub handshake_timeout: Duration,
+    pub idle_timeout: Duration,
+}
+
+// Changes in who is connected, reported by HostSession::poll
+#[derive(Clone, Debug, PartialEq)]
+pub enum HostEvent {
+    Joined,
+    Left(String),
+}
+
+impl HostSession {
+    pub fn bind(addr: impl ToSocketAddrs, config: &GameConfig) -> Result<Self, NetError> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            config_hash: config.gameplay_hash(),
+            peer: None,
+            accepted_at: Instant::now(),
+            welcomed: false,
+            remote_input: Input::default(),
+            handshake_timeout: HANDSHAKE_TIMEOUT,
+            idle_timeout: IDLE_TIMEOUT,
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<std::net::SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.welcomed
+    }
+
+    // Player two's latest input; held until a newer one arrives
+    pub fn remote_input(&self) -> Input {
+        self.remote_input
+    }
+
+    // Let a player in if nobody is playing yet, turn anyone else away and
+    // read what the player sent
+    pub fn poll(&mut self) -> Vec<HostEvent> {
+        let mut events = Vec::new();
+        if !self.welcomed
+            && self.accepted_at.elapsed() >= self.handshake_timeout
+            && let Some(peer) = self.peer.take()
+        {
+            eprintln!("Dropping a connection that never said hello");
+            peer.close();
+        }
+        loop {
+            match self.listener.accept() {
+                Ok((stream, _)) => match Connection::new(stream) {
+                    Ok(peer) if self.peer.is_none() => {
+                        self.peer = Some(peer);
+                        self.accepted_at = Instant::now();
+                    }
+                    Ok(mut extra) => {
+                        let _ = extra.send(&Message::Reject("match in progress".to_string()));
+                        extra.close();
+                    }
+                    Err(e) => eprintln!("Failed to set up connection: {}", e),
+                },
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    eprintln!("Failed to accept connection: {}", e);
+                    break;
+                }
+            }
+        }
+        let Some(peer) = &mut self.peer else {
+            return events;
+        };
+        match peer.receive() {
+            Ok(messages) => {
+                for message in messages {
+                    match message {
+                        Message::Hello {
+                            version,
+                            config_hash,
+                        } if !self.welcomed => {
+                            let reason = if version != PROTOCOL_VERSION {
+                                Some(format!(
+                                    "protocol version {} instead of {}",
+                                    version, PROTOCOL_VERSION
+                                ))
+                            } else if config_hash != self.config_hash {
+                                Some("a different game config".to_string())
+                            } else {
+                                None
+                            };
+                            if let Some(reason) = reason {
+                                let _ = peer.send(&Message::Reject(reason));
+                                if let Some(peer) = self.peer.take() {
+                                    peer.close();
+                                }
+                                return events;
+                            }
+                            if peer.send(&Message::Welcome).is_ok() {
+                                self.welcomed = true;
+                                events.push(HostEvent::Joined);
+                            }
+                        }
+                        Message::Input(input) if self.welcomed => self.remote_input = input,
+                        _ => {}
+                    }
+                }
+            }
+            Err(e) => {
+                if self.welcomed {
+                    events.push(HostEvent::Left(e.to_string()));
+                }
+                self.disconnect();
+            }
+        }
+        // Gone without closing the connection
+        if self.welcomed
+            && let Some(peer) = &self.peer
+            && peer.idle_for() >= self.idle_timeout
+        {
+            events.push(HostEvent::Left(NetError::TimedOut.to_string()));
+            self.disconnect();
+        }
+        events
+    }
+
+    pub fn send_snapshot(&mut self, snapshot: Snapshot) -> Vec<HostEvent> {
+        let Some(peer) = self.peer.as_mut().filter(|_| self.welcomed) else {
+            return Vec::new();
+        };
+        match peer.send(&Message::Snapshot(snapshot)) {
+            Ok(()) => Vec::new(),
+            Err(e) => {
+                self.disconnect();
+                vec![HostEvent::Left(e.to_string())]
+            }
+        }
+    }
+
+    fn disconnect(&mut self) {
+        self.peer = None;
+        self.welcomed = false;
+        self.remote_input = Input::default();
+    }
+}
+
+// The joining side: sends its input every frame and draws what the host sends back
+pub struct ClientSession {
+    connection: Connection,
+    welcomed: bool,
+    interpolator: Interpolator,
+    pub idle_timeout: Duration, // covers waiting for the welcome too
+}
+
+impl ClientSession {
+    pub fn connect(addr: impl ToSocketAddrs, config: &GameConfig) -> Result<Self, NetError> {
+        let mut last_error = io::Error::new(io::ErrorKind::NotFound, "no address to connect to");
+        for addr in addr.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT) {
+                Ok(stream) => {
+                    let mut connection = Connection::new(stream)?;
+                    connection.send(&Message::Hello {
+                        version: PROTOCOL_VERSION,
+                        config_hash: config.gameplay_hash(),
+                    })?;
+                    return Ok(Self {
+                        connection,
+                        welcomed: false,
+                        interpolator: Interpolator::default(),
+                        idle_timeout: IDLE_TIMEOUT,
+                    });
+                }
+                Err(e) => last_error = e,
+            }
+        }
+        Err(last_error.into())
+    }
+
+    pub fn is_welcomed(&self) -> bool {
+        self.welcomed
+    }
+
+    pub fn send_input(&mut self, input: Input) -> Result<(), NetError> {
+        self.connection.send(&Message::Input(input))
+    }
+
+    // Take in what the host sent; returns the events from the new snapshots
+    pub fn poll(&mut self, now: Instant) -> Result<Vec<SimEvent>, NetError> {
+        let mut events = Vec::new();
+        for message in self.connection.receive()? {
+            match message {
+                Message::Welcome => self.welcomed = true,
+                Message::Reject(reason) => return Err(NetError::Rejected(reason)),
+                Message::Snapshot(mut snapshot) => {
+                    events.append(&mut snapshot.events);
+                    self.interpolator.push(now, snapshot);
+                }
+                _ => {}
+            }
+        }
+        if self.connection.idle_for() >= self.idle_timeout {
+            return Err(NetError::TimedOut);
+        }
+        Ok(events)
+    }
+
+    pub fn snapshot(&self, now: Instant) -> Option<Snapshot> {
+        self.interpolator.sample(now)
+    }
+}
+
+// Which side of a network match this game is on
+pub enum Network {
+    Host(HostSession),
+    Client(Box<ClientSession>),
+    // A client whose host has gone; kept so the game doesn't carry on as a local match
+    Disconnected,
+}
@@ -0,0 +1,114 @@
+use crate::config::{CONFIG_FILE, ConfigError, GameConfig};
+use crate::net::DEFAULT_PORT;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+
+// Command line flags accepted by the game binary
+#[derive(Debug, Default, PartialEq)]
+pub struct LaunchOptions {
+    pub seed: Option<u64>,
+    pub config: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    pub two_player: bool,
+    pub host: Option<String>, // address to accept player two on
+    pub join: Option<String>, // address of the host to play against
+    pub headless: bool,       // run the network match without a window
+    pub demo: bool,
+    pub no_shake: bool,
+    pub levels: Option<PathBuf>, // level directory to play, overriding levels_dir
+}
+
+impl LaunchOptions {
+    pub fn parse<I>(args: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => {
+                    let value = args.next().ok_or("--seed needs a value")?;
+                    let seed = value
+                        .parse()
+                        .map_err(|_| format!("invalid seed '{}'", value))?;
+                    options.seed = Some(seed);
+                }
+                "--config" => {
+                    let value = args.next().ok_or("--config needs a path")?;
+                    options.config = Some(PathBuf::from(value));
+                }
+                "--levels" => {
+                    let value = args.next().ok_or("--levels needs a directory")?;
+                    options.levels = Some(PathBuf::from(value));
+                }
+                "--replay" => {
+                    let value = args.next().ok_or("--replay needs a path")?;
+                    options.replay = Some(PathBuf::from(value));
+                }
+                "--two-player" => options.two_player = true,
+                "--demo" => options.demo = true,
+                "--no-shake" => options.no_shake = true,
+                "--headless" => options.headless = true,
+                "--host" => {
+                    let value = args.next().ok_or("--host needs an address")?;
+                    options.host = Some(with_default_port(value));
+                }
+                "--join" => {
+                    let value = args.next().ok_or("--join needs an address")?;
+                    options.join = Some(with_default_port(value));
+                }
+                _ => return Err(format!("unknown argument '{}'", arg)),
+            }
+        }
+        if options.host.is_some() && options.join.is_some() {
+            return Err("--host and --join can't be used together".to_string());
+        }
+        if options.headless && options.host.is_none() && options.join.is_none() {
+            return Err("--headless needs --host or --join".to_string());
+        }
+        Ok(options)
+    }
+
+    pub fn seed_or_random(&self) -> u64 {
+        self.seed.unwrap_or_else(rand::random)
+    }
+
+    // An explicit --config must load; otherwise bounce_shield.toml in the
+    // working directory is used if there is one, falling back to the defaults.
+    // The flags then win over the file: --two-player switches the mode on, as
+    // do --host and --join since network games are always matches, --no-shake
+    // turns screen shake off and --levels picks the level directory.
+    pub fn game_config(&self) -> Result<GameConfig, ConfigError> {
+        let mut config = match &self.config {
+            Some(path) => GameConfig::load(path)?,
+            None if Path::new(CONFIG_FILE).exists() => GameConfig::load(Path::new(CONFIG_FILE))?,
+            None => GameConfig::default(),
+        };
+        config.two_player |= self.two_player || self.host.is_some() || self.join.is_some();
+        config.screen_shake &= !self.no_shake;
+        if let Some(dir) = &self.levels {
+            config.levels_dir = dir.clone();
+        }
+        Ok(config)
+    }
+}
+
+// "192.168.1.5", "::1", "[::1]" and "localhost" mean the default port;
+// "[::1]:9000" and "localhost:9000" give their own
+fn with_default_port(addr: String) -> String {
+    if addr.parse::<SocketAddr>().is_ok() {
+        return addr;
+    }
+    let unbracketed = addr
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .unwrap_or(&addr);
+    if let Ok(ip) = unbracketed.parse::<IpAddr>() {
+        SocketAddr::new(ip, DEFAULT_PORT).to_string()
+    } else if addr.contains(':') {
+        addr
+    } else {
+        format!("{}:{}", addr, DEFAULT_PORT)
+    }
+}
@@ -0,0 +1,120 @@
+use ggez::graphics::Color;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::f32::consts::PI;
+
+// Enough for several bursts and an explosion at once
+pub const MAX_PARTICLES: usize = 512;
+pub const PARTICLE_SIZE: f32 = 4.0;
+// Pulls sparks down a little so they arc rather than fly straight
+const PARTICLE_GRAVITY: f32 = 400.0; // pixels per second squared
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Particle {
+    pub x: f32,
+    pub y: f32,
+    pub dx: f32,
+    pub dy: f32,
+    pub age: f32,      // seconds
+    pub lifetime: f32, // seconds
+    pub color: Color,
+}
+
+impl Particle {
+    // Fades out over its lifetime
+    pub fn current_color(&self) -> Color {
+        let mut color = self.color;
+        color.a *= (1.0 - self.age / self.lifetime).clamp(0.0, 1.0);
+        color
+    }
+}
+
+// A fixed pool: live particles are kept at the front and dead ones are
+// swapped out, so nothing is allocated after start-up. When the pool is full
+// new particles are dropped. Uses its own RNG so effects never touch the
+// simulation's and replays stay the same.
+pub struct Particles {
+    pool: Vec<Particle>,
+    capacity: usize,
+    rng: StdRng,
+}
+
+impl Default for Particles {
+    fn default() -> Self {
+        Self::new(MAX_PARTICLES)
+    }
+}
+
+impl Particles {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            pool: Vec::with_capacity(capacity),
+            capacity,
+            rng: StdRng::seed_from_u64(0),
+        }
+    }
+
+    pub fn alive(&self) -> &[Particle] {
+        &self.pool
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn clear(&mut self) {
+        self.pool.clear();
+    }
+
+    // Sparks thrown off a surface, spread around the way it faces
+    pub fn burst(&mut self, x: f32, y: f32, normal: [f32; 2], count: usize, color: Color) {
+        let facing = normal[1].atan2(normal[0]);
+        for _ in 0..count {
+            let angle = facing + self.rng.gen_range(-PI / 3.0..PI / 3.0);
+            let speed = self.rng.gen_range(80.0..240.0);
+            let lifetime = self.rng.gen_range(0.2..0.5);
+            self.emit(x, y, angle, speed, lifetime, color);
+        }
+    }
+
+    // A bigger, slower-fading spray in every direction
+    pub fn explode(&mut self, x: f32, y: f32, count: usize, color: Color) {
+        for _ in 0..count {
+            let angle = self.rng.gen_range(-PI..PI);
+            let speed = self.rng.gen_range(60.0..420.0);
+            let lifetime = self.rng.gen_range(0.5..1.2);
+            self.emit(x, y, angle, speed, lifetime, color);
+        }
+    }
+
+    fn emit(&mut self, x: f32, y: f32, angle: f32, speed: f32, lifetime: f32, color: Color) {
+        if self.pool.len() >= self.capacity {
+            return;
+        }
+        self.pool.push(Particle {
+            x,
+            y,
+            dx: angle.cos() * speed,
+            dy: angle.sin() * speed,
+            age: 0.0,
+            lifetime,
+            color,
+        });
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        let mut i = 0;
+        while i < self.pool.len() {
+            let particle = &mut self.pool[i];
+            particle.age += dt;
+            if particle.age >= particle.lifetime {
+                self.pool.swap_remove(i);
+                continue;
+            }
+            particle.dy += PARTICLE_GRAVITY * dt;
+            particle.x += particle.dx * dt;
+            particle.y += particle.dy * dt;
+            i += 1;
+        }
+    }
+}
@@ -0,0 +1,109 @@
+// Axis-aligned box, positioned by its top-left corner like everything drawn on screen
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
+
+impl Aabb {
+    pub fn new(x: f32, y: f32, w: f32, h: f32) -> Self {
+        Self { x, y, w, h }
+    }
+
+    pub fn overlaps(&self, other: &Aabb) -> bool {
+        self.x < other.x + other.w
+            && self.x + self.w > other.x
+            && self.y < other.y + other.h
+            && self.y + self.h > other.y
+    }
+
+    pub fn center(&self) -> [f32; 2] {
+        [self.x + self.w / 2.0, self.y + self.h / 2.0]
+    }
+}
+
+// First contact of a sweep: `time` is the fraction of the movement travelled
+// before touching, `normal` points out of the surface that was hit
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hit {
+    pub time: f32,
+    pub normal: [f32; 2],
+}
+
+// Entry and exit times along one axis, or None if the boxes never line up on it
+fn axis_times(
+    pos: f32,
+    size: f32,
+    delta: f32,
+    target_pos: f32,
+    target_size: f32,
+) -> Option<(f32, f32)> {
+    if delta == 0.0 {
+        // Not moving on this axis: either always overlapping on it or never
+        return if pos < target_pos + target_size && pos + size > target_pos {
+            Some((f32::NEG_INFINITY, f32::INFINITY))
+        } else {
+            None
+        };
+    }
+    let (near, far) = if delta > 0.0 {
+        (target_pos - (pos + size), target_pos + target_size - pos)
+    } else {
+        (target_pos + target_size - pos, target_pos - (pos + size))
+    };
+    Some((near / delta, far / delta))
+}
+
+// Swept AABB test of `moving` travelling by `delta` against a static `target`.
+// Boxes that already overlap are not reported; callers separate those instead.
+pub fn sweep(moving: Aabb, delta: [f32; 2], target: Aabb) -> Option<Hit> {
+    let (entry_x, exit_x) = axis_times(moving.x, moving.w, delta[0], target.x, target.w)?;
+    let (entry_y, exit_y) = axis_times(moving.y, moving.h, delta[1], target.y, target.h)?;
+
+    let entry = entry_x.max(entry_y);
+    let exit = exit_x.min(exit_y);
+    if entry >= exit || !(0.0..=1.0).contains(&entry) {
+        return None;
+    }
+
+    let normal = if entry_x > entry_y {
+        [-delta[0].signum(), 0.0]
+    } else {
+        [0.0, -delta[1].signum()]
+    };
+    Some(Hit {
+        time: entry,
+        normal,
+    })
+}
+
+// Flip the velocity component heading into the surface
+pub fn reflect(velocity: [f32; 2], normal: [f32; 2]) -> [f32; 2] {
+    let mut reflected = velocity;
+    if normal[0] != 0.0 && velocity[0] * normal[0] < 0.0 {
+        reflected[0] = -velocity[0];
+    }
+    if normal[1] != 0.0 && velocity[1] * normal[1] < 0.0 {
+        reflected[1] = -velocity[1];
+    }
+    reflected
+}
+
+// Outgoing velocity off the bar. `offset` is where the ball landed, from -1 at
+// the left edge to 1 at the right edge: the center sends it straight up, the
+// edges up to `max_angle` radians off vertical. Speed is kept, but the ball
+// always leaves upwards at `min_vertical_speed` or faster.
+pub fn paddle_bounce(
+    velocity: [f32; 2],
+    offset: f32,
+    max_angle: f32,
+    min_vertical_speed: f32,
+) -> [f32; 2] {
+    let speed = velocity[0].hypot(velocity[1]);
+    let angle = offset.clamp(-1.0, 1.0) * max_angle;
+    let dx = speed * angle.sin();
+    let dy = (speed * angle.cos()).max(min_vertical_speed);
+    [dx, -dy]
+}
@@ -0,0 +1,80 @@
+use crate::game::TICK_RATE;
+use crate::physics::Aabb;
+use std::time::Duration;
+
+pub const POWERUP_DURATION: Duration = Duration::from_secs(10);
+pub const POWERUP_DURATION_TICKS: u64 = POWERUP_DURATION.as_secs() * TICK_RATE as u64;
+pub const POWERUP_SIZE: f32 = 30.0;
+// Pickups waiting on the field at once; no more spawn until one is collected
+pub const MAX_POWER_UPS: usize = 3;
+pub const WIDTH_INCREASE_FACTOR: f32 = 1.5;
+pub const SPEED_BOOST_FACTOR: f32 = 1.5;
+pub const SLOW_BALL_FACTOR: f32 = 0.75;
+// Balls added by a Multi-Ball pickup, and the angle between them in radians
+pub const MULTI_BALL_EXTRA: usize = 2;
+pub const MULTI_BALL_SPREAD: f32 = 0.35;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PowerUpType {
+    WidthIncrease,
+    SpeedBoost,
+    ExtraHeart,
+    SlowBall,
+    MultiBall,
+}
+
+impl PowerUpType {
+    pub const ALL: [PowerUpType; 5] = [
+        PowerUpType::WidthIncrease,
+        PowerUpType::SpeedBoost,
+        PowerUpType::ExtraHeart,
+        PowerUpType::SlowBall,
+        PowerUpType::MultiBall,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PowerUpType::WidthIncrease => "Wide Bar",
+            PowerUpType::SpeedBoost => "Speed Boost",
+            PowerUpType::ExtraHeart => "Extra Heart",
+            PowerUpType::SlowBall => "Slow Ball",
+            PowerUpType::MultiBall => "Multi-Ball",
+        }
+    }
+
+    // Extra hearts and extra balls are handed out once; everything else wears off
+    pub fn is_timed(self) -> bool {
+        !matches!(self, PowerUpType::ExtraHeart | PowerUpType::MultiBall)
+    }
+}
+
+// A pickup waiting on the field for the ball to run into it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PowerUp {
+    pub x: f32,
+    pub y: f32,
+    pub power_type: PowerUpType,
+    pub size: f32,
+}
+
+impl PowerUp {
+    pub fn new(x: f32, y: f32, power_type: PowerUpType) -> Self {
+        Self {
+            x,
+            y,
+            power_type,
+            size: POWERUP_SIZE,
+        }
+    }
+
+    pub fn aabb(&self) -> Aabb {
+        Aabb::new(self.x, self.y, self.size, self.size)
+    }
+}
+
+// A collected power-up that is still working, until the simulation reaches `until_tick`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ActiveEffect {
+    pub power_type: PowerUpType,
+    pub until_tick: u64,
+}
@@ -0,0 +1,295 @@
+use crate::config::GameConfig;
+use crate::data_file::write_atomically;
+use crate::game::{GameState, Input, Simulation};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub const REPLAY_DIR: &str = "replays";
+pub const REPLAY_EXTENSION: &str = "bsr";
+const REPLAY_MAGIC: &[u8; 4] = b"BSRP";
+const REPLAY_VERSION: u8 = 1;
+// Set in a run's button bits when a pointer target follows the axis
+const TARGET_FLAG: u8 = 1 << 7;
+const HEADER_LEN: usize = 4 + 1 + 8 + 8 + 8 + 8 + 1 + 4;
+// Bits, axis and length: the smallest a run of inputs can be
+const MIN_RUN_LEN: usize = 2 + 2;
+
+// One run from its first tick: the seed, the tuning it was played with, the
+// session state the rules depend on, and the input of every tick
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub config_hash: u64,
+    pub high_score: usize,
+    pub prev_high_score: usize,
+    pub first_start: bool,
+    pub inputs: Vec<Input>,
+    pub player_two: Vec<Input>, // empty unless it was a two-player match
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    NotAReplay,
+    UnsupportedVersion(u8),
+    Truncated,
+    Corrupt,
+    ConfigMismatch,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(e) => write!(f, "failed to access replay: {}", e),
+            ReplayError::NotAReplay => write!(f, "not a bounce_shield replay"),
+            ReplayError::UnsupportedVersion(v) => write!(f, "unsupported replay version {}", v),
+            ReplayError::Truncated => write!(f, "replay file is truncated"),
+            ReplayError::Corrupt => write!(f, "replay file is corrupt"),
+            ReplayError::ConfigMismatch => {
+                write!(f, "replay was recorded with a different game config")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<io::Error> for ReplayError {
+    fn from(e: io::Error) -> Self {
+        ReplayError::Io(e)
+    }
+}
+
+impl Replay {
+    // Start recording the run `sim` is about to play
+    pub fn start(sim: &Simulation) -> Self {
+        Self {
+            seed: sim.seed,
+            config_hash: sim.config.gameplay_hash(),
+            high_score: sim.high_score,
+            prev_high_score: sim.prev_high_score,
+            first_start: sim.first_start,
+            inputs: Vec::new(),
+            player_two: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, input: Input) {
+        self.inputs.push(input);
+    }
+
+    // Called alongside `record` every tick of a two-player match
+    pub fn record_player_two(&mut self, input: Input) {
+        self.player_two.push(input);
+    }
+
+    // Player two's input for `tick`, idle when there is none
+    pub fn player_two_input(&self, tick: usize) -> Input {
+        self.player_two.get(tick).copied().unwrap_or_default()
+    }
+
+    pub fn file_name(&self) -> String {
+        format!("run-{}.{}", self.seed, REPLAY_EXTENSION)
+    }
+
+    // For a run the player took over when this replay's inputs ran out
+    pub fn takeover_file_name(&self) -> String {
+        format!("run-{}-takeover.{}", self.seed, REPLAY_EXTENSION)
+    }
+
+    // Inputs only reproduce a run under the tuning they were recorded with
+    pub fn check_config(&self, config: &GameConfig) -> Result<(), ReplayError> {
+        if config.gameplay_hash() == self.config_hash {
+            Ok(())
+        } else {
+            Err(ReplayError::ConfigMismatch)
+        }
+    }
+
+    // Put `sim` back at the start of the recorded run
+    pub fn restore(&self, sim: &mut Simulation) {
+        sim.reset_with_seed(self.seed);
+        sim.high_score = self.high_score;
+        sim.prev_high_score = self.prev_high_score;
+        sim.first_start = self.first_start;
+    }
+
+    // Play the whole run headless and hand back the final state
+    pub fn simulate(&self, config: GameConfig) -> Result<Simulation, ReplayError> {
+        self.check_config(&config)?;
+        let mut sim = Simulation::new(config, self.seed);
+        self.restore(&mut sim);
+        for (tick, &input) in self.inputs.iter().enumerate() {
+            if sim.state != GameState::Playing {
+                break;
+            }
+            sim.step_players(input, self.player_two_input(tick));
+        }
+        Ok(sim)
+    }
+
+    // Header followed by run-length encoded inputs: (bits, axis, [target], tick count)
+    // runs, then the same again for player two if they played
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + 16);
+        bytes.extend_from_slice(REPLAY_MAGIC);
+        bytes.push(REPLAY_VERSION);
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&self.config_hash.to_le_bytes());
+        bytes.extend_from_slice(&(self.high_score as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.prev_high_score as u64).to_le_bytes());
+        bytes.push(self.first_start as u8);
+        bytes.extend_from_slice(&(self.inputs.len() as u32).to_le_bytes());
+
+        write_runs(&mut bytes, &self.inputs);
+        if !self.player_two.is_empty() {
+            write_runs(&mut bytes, &self.player_two);
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ReplayError> {
+        if bytes.len() < 5 || &bytes[..4] != REPLAY_MAGIC {
+            return Err(ReplayError::NotAReplay);
+        }
+        let version = bytes[4];
+        if version != REPLAY_VERSION {
+            return Err(ReplayError::UnsupportedVersion(version));
+        }
+        if bytes.len() < HEADER_LEN {
+            return Err(ReplayError::Truncated);
+        }
+        let u64_at = |at: usize| u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap());
+        let tick_count = u32::from_le_bytes(bytes[38..42].try_into().unwrap()) as usize;
+
+        let mut rest = &bytes[HEADER_LEN..];
+        // No file can hold more ticks than its runs add up to, whatever the header says
+        if tick_count > rest.len() / MIN_RUN_LEN * u16::MAX as usize {
+            return Err(ReplayError::Truncated);
+        }
+        let inputs = read_runs(&mut rest, tick_count)?;
+        let player_two = if !rest.is_empty() {
+            read_runs(&mut rest, tick_count)?
+        } else {
+            Vec::new()
+        };
+        if !rest.is_empty() {
+            return Err(ReplayError::Truncated);
+        }
+
+        Ok(Self {
+            seed: u64_at(5),
+            config_hash: u64_at(13),
+            high_score: u64_at(21) as usize,
+            prev_high_score: u64_at(29) as usize,
+            first_start: bytes[37] != 0,
+            inputs,
+            player_two,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ReplayError> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        write_atomically(path, self.to_bytes())
+    }
+}
+
+// One tick's input as replays and network messages both store it: the
+// button bits, the stick axis and, when TARGET_FLAG is set, the pointer target
+impl Input {
+    pub fn write_bytes(self, bytes: &mut Vec<u8>) {
+        match self.target {
+            Some(target) => {
+                bytes.push(self.to_bits() | TARGET_FLAG);
+                bytes.push(self.axis as u8);
+                bytes.extend_from_slice(&target.to_le_bytes());
+            }
+            None => {
+                bytes.push(self.to_bits());
+                bytes.push(self.axis as u8);
+            }
+        }
+    }
+
+    // None if `rest` ends part way through
+    pub fn read_bytes(rest: &mut &[u8]) -> Option<Self> {
+        let [bits, axis] = take(rest).ok()?;
+        let mut input = Input::from_bits(bits & !TARGET_FLAG);
+        input.axis = axis as i8;
+        if bits & TARGET_FLAG != 0 {
+            input.target = Some(u16::from_le_bytes(take(rest).ok()?));
+        }
+        Some(input)
+    }
+}
+
+fn write_runs(bytes: &mut Vec<u8>, inputs: &[Input]) {
+    let mut ticks = inputs.iter().peekable();
+    while let Some(input) = ticks.next() {
+        let mut run: u16 = 1;
+        while run < u16::MAX && ticks.peek() == Some(&input) {
+            ticks.next();
+            run += 1;
+        }
+        input.write_bytes(bytes);
+        bytes.extend_from_slice(&run.to_le_bytes());
+    }
+}
+
+// Runs adding up to exactly `tick_count` ticks. Each run is checked before
+// it is expanded, so a damaged file can't make us allocate for ticks it
+// doesn't have.
+fn read_runs(rest: &mut &[u8], tick_count: usize) -> Result<Vec<Input>, ReplayError> {
+    let mut inputs = Vec::new();
+    while inputs.len() < tick_count {
+        let input = Input::read_bytes(rest).ok_or(ReplayError::Truncated)?;
+        let length = u16::from_le_bytes(take(rest)?) as usize;
+        if length == 0 || length > tick_count - inputs.len() {
+            return Err(ReplayError::Corrupt);
+        }
+        inputs.extend(std::iter::repeat_n(input, length));
+    }
+    Ok(inputs)
+}
+
+// Split the next N bytes off the front of `rest`
+fn take<const N: usize>(rest: &mut &[u8]) -> Result<[u8; N], ReplayError> {
+    let Some((head, tail)) = rest.split_first_chunk::<N>() else {
+        return Err(ReplayError::Truncated);
+    };
+    *rest = tail;
+    Ok(*head)
+}
+
+// Feeds a recorded run back one tick at a time
+pub struct ReplayPlayer {
+    pub replay: Replay,
+    pub tick: usize,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        Self { replay, tick: 0 }
+    }
+
+    pub fn next_input(&mut self) -> Option<Input> {
+        self.next_inputs().map(|(input, _)| input)
+    }
+
+    // Both players' input for the next tick
+    pub fn next_inputs(&mut self) -> Option<(Input, Input)> {
+        let input = self.replay.inputs.get(self.tick).copied()?;
+        let player_two = self.replay.player_two_input(self.tick);
+        self.tick += 1;
+        Some((input, player_two))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.tick >= self.replay.inputs.len()
+    }
+}
@@ -0,0 +1,112 @@
+use crate::audio_settings::AudioSettings;
+use rodio::source::Buffered;
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source};
+use std::io::Cursor;
+
+// The clips are built into the binary, so a released game needs no files
+// next to it
+pub const MUSIC: &[u8] = include_bytes!("../docs/assets/audio/game_tune.wav");
+
+type Clip = Buffered<Decoder<Cursor<&'static [u8]>>>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sound {
+    Bounce,
+    Heart,
+    Start,
+    GameOver,
+}
+
+impl Sound {
+    pub const ALL: [Sound; 4] = [Sound::Bounce, Sound::Heart, Sound::Start, Sound::GameOver];
+
+    pub fn data(self) -> &'static [u8] {
+        match self {
+            Sound::Bounce => include_bytes!("../docs/assets/audio/game_bounce.wav"),
+            Sound::Heart => include_bytes!("../docs/assets/audio/game_heart.wav"),
+            Sound::Start => include_bytes!("../docs/assets/audio/game_start.wav"),
+            Sound::GameOver => include_bytes!("../docs/assets/audio/game_over.wav"),
+        }
+    }
+}
+
+// Every sound decoded once up front. With no audio device the whole bank is
+// silent, and a clip that won't decode leaves just that sound silent;
+// playback errors are ignored the same way, so audio trouble never stops the
+// game.
+#[derive(Default)]
+pub struct SoundBank {
+    output: Option<(OutputStream, OutputStreamHandle)>,
+    effects: Vec<(Sound, Clip)>,
+    music: Option<Sink>, // paused until start_music
+    effects_volume: f32,
+}
+
+impl SoundBank {
+    pub fn load() -> Self {
+        let Ok((stream, handle)) = OutputStream::try_default() else {
+            eprintln!("No audio device found, playing without sound");
+            return Self::default();
+        };
+        let effects = Sound::ALL
+            .into_iter()
+            .filter_map(|sound| Some((sound, decode(sound.data())?)))
+            .collect();
+        let music = decode(MUSIC).and_then(|clip| {
+            let sink = Sink::try_new(&handle).ok()?;
+            sink.pause();
+            sink.append(clip.repeat_infinite());
+            Some(sink)
+        });
+        Self {
+            output: Some((stream, handle)),
+            effects,
+            music,
+            effects_volume: 1.0,
+        }
+    }
+
+    pub fn apply_settings(&mut self, settings: &AudioSettings) {
+        self.effects_volume = settings.effects_volume();
+        if let Some(music) = &self.music {
+            music.set_volume(settings.music_volume());
+        }
+    }
+
+    pub fn is_loaded(&self, sound: Sound) -> bool {
+        self.effects.iter().any(|(s, _)| *s == sound)
+    }
+
+    pub fn has_music(&self) -> bool {
+        self.music.is_some()
+    }
+
+    // Each play is a fresh copy of the clip, so quick bounces overlap
+    // instead of cutting each other off
+    pub fn play(&mut self, sound: Sound) {
+        if self.effects_volume <= 0.0 {
+            return;
+        }
+        if let Some((_, handle)) = &self.output
+            && let Some((_, clip)) = self.effects.iter().find(|(s, _)| *s == sound)
+        {
+            let _ = handle.play_raw(clip.clone().amplify(self.effects_volume).convert_samples());
+        }
+    }
+
+    pub fn start_music(&mut self) {
+        if let Some(music) = &self.music {
+            music.play();
+        }
+    }
+
+    pub fn stop_music(&mut self) {
+        if let Some(music) = &self.music {
+            music.pause();
+        }
+    }
+}
+
+fn decode(data: &'static [u8]) -> Option<Clip> {
+    Decoder::new(Cursor::new(data)).ok().map(Source::buffered)
+}
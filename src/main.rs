@@ -1,8 +1,42 @@
-use bounce_shield::{Game, create_game_ctx};
+use bounce_shield::{
+    Difficulty, Game, LaunchOptions, Replay, create_game_ctx, load_levels, run_headless,
+};
 use ggez::event;
+use std::path::Path;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let (mut ctx, event_loop) = create_game_ctx()?;
-    let game = Game::new(&mut ctx)?;
+    let options = LaunchOptions::parse(std::env::args().skip(1))?;
+    let mut config = options.game_config()?;
+    if !config.levels_dir.as_os_str().is_empty() {
+        config.levels = load_levels(&config.levels_dir, &config)?;
+    }
+    let seed = options.seed_or_random();
+    println!("seed: {}", seed);
+
+    // No window or sound, and the replay goes in the working directory
+    if options.headless {
+        let mut game = Game::headless(config, seed, Path::new("."));
+        if let Some(addr) = &options.host {
+            println!("hosting on {}", game.host(addr.as_str())?);
+        } else if let Some(addr) = &options.join {
+            game.join(addr.as_str())?;
+        }
+        println!("result: {}", run_headless(&mut game)?);
+        return Ok(());
+    }
+
+    let (mut ctx, event_loop) = create_game_ctx(&config)?;
+    let mut game = Game::new(&mut ctx, config, seed)?;
+    if let Some(path) = &options.replay {
+        game.play_replay(Replay::load(path)?)?;
+    }
+    if options.demo {
+        game.start_demo(Difficulty::Normal);
+    }
+    if let Some(addr) = &options.host {
+        game.host(addr.as_str())?;
+    } else if let Some(addr) = &options.join {
+        game.join(addr.as_str())?;
+    }
     event::run(ctx, event_loop, game)
 }
@@ -0,0 +1,75 @@
+mod common;
+
+use bounce_shield::{AudioSettings, Channel, VOLUME_STEP};
+use common::TempDir;
+
+#[test]
+fn test_channels_scale_with_master() {
+    let settings = AudioSettings {
+        master: 0.5,
+        music: 0.4,
+        effects: 1.0,
+        muted: false,
+    };
+    assert!((settings.music_volume() - 0.2).abs() < 1e-6);
+    assert!((settings.effects_volume() - 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn test_mute_silences_everything_and_keeps_levels() {
+    let mut settings = AudioSettings::default();
+    settings.toggle_mute();
+    assert_eq!(settings.music_volume(), 0.0);
+    assert_eq!(settings.effects_volume(), 0.0);
+    settings.toggle_mute();
+    assert_eq!(
+        settings.effects_volume(),
+        AudioSettings::default().effects_volume()
+    );
+}
+
+#[test]
+fn test_adjust_clamps_and_lands_on_steps() {
+    let mut settings = AudioSettings::default();
+    for _ in 0..3 {
+        settings.adjust(Channel::Master, -VOLUME_STEP);
+    }
+    assert!((settings.volume(Channel::Master) - 0.7).abs() < 1e-6);
+    for _ in 0..3 {
+        settings.adjust(Channel::Master, VOLUME_STEP);
+    }
+    assert_eq!(settings.volume(Channel::Master), 1.0);
+    settings.adjust(Channel::Master, VOLUME_STEP);
+    assert_eq!(settings.volume(Channel::Master), 1.0);
+
+    for _ in 0..20 {
+        settings.adjust(Channel::Music, -VOLUME_STEP);
+    }
+    assert_eq!(settings.volume(Channel::Music), 0.0);
+}
+
+#[test]
+fn test_parse_is_tolerant() {
+    let settings = AudioSettings::parse("master = 3.0\nmuted = true\n").unwrap();
+    assert_eq!(settings.master, 1.0);
+    assert!(settings.muted);
+    assert_eq!(settings.music, AudioSettings::default().music);
+    assert_eq!(AudioSettings::parse("master = \"loud\""), None);
+}
+
+#[test]
+fn test_save_and_load_round_trip() {
+    let dir = TempDir::new();
+    let path = dir.join("audio_settings.toml");
+    let settings = AudioSettings {
+        master: 0.3,
+        music: 0.0,
+        effects: 0.8,
+        muted: true,
+    };
+    settings.save(&path).unwrap();
+    assert_eq!(AudioSettings::load(&path), settings);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(AudioSettings::load(&path), AudioSettings::default());
+}
@@ -0,0 +1,156 @@
+mod common;
+
+use bounce_shield::{
+    Autopilot, Ball, Difficulty, Game, GameConfig, GameState, Input, REPLAY_DIR, Simulation,
+    TICK_DT, predict_crossing,
+};
+use common::TempDir;
+use std::time::Duration;
+
+fn ball(x: f32, y: f32, dx: f32, dy: f32) -> Ball {
+    Ball {
+        x,
+        y,
+        dx,
+        dy,
+        size: 20.0,
+    }
+}
+
+// No reaction delay and no aiming error
+fn perfect(seed: u64) -> Autopilot {
+    let mut autopilot = Autopilot::new(Difficulty::Hard, seed);
+    autopilot.reaction_ticks = 0;
+    autopilot.error = 0.0;
+    autopilot
+}
+
+#[test]
+fn test_predicts_straight_drop() {
+    let (seconds, x) =
+        predict_crossing(&ball(500.0, 100.0, 0.0, 200.0), 1020.0, 1920.0, true).unwrap();
+    assert_eq!(seconds, 4.5);
+    assert_eq!(x, 500.0);
+}
+
+#[test]
+fn test_predicts_bounces_off_side_walls() {
+    // Heads 900 px right from x = 1800, meeting the right wall at 1900
+    let (_, x) =
+        predict_crossing(&ball(1800.0, 100.0, 200.0, 200.0), 1020.0, 1920.0, true).unwrap();
+    assert!((x - 1100.0).abs() < 1e-3);
+    let (_, x) =
+        predict_crossing(&ball(100.0, 100.0, -200.0, 200.0), 1020.0, 1920.0, true).unwrap();
+    assert!((x - 800.0).abs() < 1e-3);
+}
+
+#[test]
+fn test_rising_ball_comes_back_off_the_top_wall() {
+    let rising = ball(500.0, 100.0, 0.0, -200.0);
+    let (seconds, _) = predict_crossing(&rising, 1020.0, 1920.0, true).unwrap();
+    assert_eq!(seconds, 5.5);
+    // With a second bar at the top there's no telling how it comes back
+    assert_eq!(predict_crossing(&rising, 1020.0, 1920.0, false), None);
+    // Nor once the ball is already past the bar
+    assert_eq!(
+        predict_crossing(&ball(500.0, 1050.0, 0.0, 200.0), 1020.0, 1920.0, true),
+        None
+    );
+}
+
+#[test]
+fn test_steers_toward_the_crossing() {
+    let mut sim = Simulation::new(GameConfig::default(), 1);
+    let mut autopilot = perfect(1);
+    sim.balls[0] = ball(100.0, 500.0, 0.0, 300.0);
+    assert_eq!(
+        autopilot.next_input(&sim),
+        Input {
+            left: true,
+            ..Input::default()
+        }
+    );
+    sim.balls[0].x = 1800.0;
+    assert!(autopilot.next_input(&sim).right);
+
+    // Stays put once under it rather than jittering
+    sim.balls[0].x = sim.bar.x + (sim.bar.width - sim.balls[0].size) / 2.0;
+    assert_eq!(autopilot.next_input(&sim), Input::default());
+}
+
+#[test]
+fn test_reaction_delay_follows_old_ball() {
+    let mut sim = Simulation::new(GameConfig::default(), 1);
+    let mut autopilot = perfect(1);
+    autopilot.reaction_ticks = 5;
+    sim.balls[0] = ball(100.0, 500.0, 0.0, 300.0);
+    for _ in 0..10 {
+        assert!(autopilot.next_input(&sim).left);
+    }
+    sim.balls[0].x = 1800.0;
+    for _ in 0..5 {
+        assert!(autopilot.next_input(&sim).left);
+    }
+    assert!(autopilot.next_input(&sim).right);
+}
+
+#[test]
+fn test_perfect_autopilot_keeps_its_hearts() {
+    let mut sim = Simulation::new(GameConfig::default(), 3);
+    let mut autopilot = perfect(3);
+    for _ in 0..20_000 {
+        let input = autopilot.next_input(&sim);
+        sim.step(input);
+    }
+    assert_eq!(sim.state, GameState::Playing);
+    assert!(sim.hearts >= sim.config.initial_hearts);
+    assert!(sim.score > 20);
+}
+
+#[test]
+fn test_autopilot_runs_are_reproducible() {
+    let run = || {
+        let mut sim = Simulation::new(GameConfig::default(), 8);
+        let mut autopilot = Autopilot::new(Difficulty::Easy, 8);
+        for _ in 0..5_000 {
+            let input = autopilot.next_input(&sim);
+            sim.step(input);
+        }
+        (sim.score, sim.hearts, sim.balls)
+    };
+    assert_eq!(run(), run());
+}
+
+#[test]
+fn test_stopping_the_demo_saves_no_replay() {
+    let dir = TempDir::new();
+    let mut game = Game::headless(GameConfig::default(), 3, &dir);
+    game.start_demo(Difficulty::Normal);
+    game.sim.state = GameState::Playing;
+    for _ in 0..30 {
+        game.frame(
+            Duration::from_secs_f32(TICK_DT),
+            (Input::default(), Input::default()),
+        );
+    }
+    assert!(!game.recording.inputs.is_empty());
+    game.stop_demo();
+    assert_eq!(game.sim.state, GameState::MainMenu);
+    assert!(!dir.join(REPLAY_DIR).exists());
+}
+
+#[test]
+fn test_demo_is_one_player_and_keeps_the_chosen_mode() {
+    let dir = TempDir::new();
+    let mut game = Game::headless(GameConfig::default(), 3, &dir);
+    game.start_mode(true);
+    game.open_menu();
+
+    game.start_demo(Difficulty::Normal);
+    assert!(game.sim.player_two.is_none());
+    assert!(!game.sim.tracks_high_score);
+    game.stop_demo();
+    assert_eq!(game.sim.state, GameState::MainMenu);
+    assert!(game.sim.config.two_player);
+    assert!(game.sim.tracks_high_score);
+}
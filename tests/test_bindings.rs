@@ -0,0 +1,103 @@
+use bounce_shield::{Action, GameConfig, KeyBindings, key_from_name, key_name};
+use ggez::input::keyboard::KeyCode;
+
+#[test]
+fn test_defaults_match_original_controls() {
+    let bindings = KeyBindings::default();
+    assert_eq!(
+        bindings.keys(Action::MoveLeft),
+        &[KeyCode::Left, KeyCode::A]
+    );
+    assert_eq!(bindings.action_for(KeyCode::Space), Some(Action::Pause));
+    assert_eq!(bindings.action_for(KeyCode::R), Some(Action::Restart));
+    assert_eq!(bindings.action_for(KeyCode::Escape), Some(Action::Menu));
+    assert_eq!(bindings.action_for(KeyCode::Z), None);
+    assert_eq!(bindings.conflict(), None);
+}
+
+#[test]
+fn test_key_names_round_trip() {
+    for key in [
+        KeyCode::A,
+        KeyCode::Key7,
+        KeyCode::F12,
+        KeyCode::Left,
+        KeyCode::Space,
+    ] {
+        assert_eq!(key_from_name(key_name(key).unwrap()), Some(key));
+    }
+    assert_eq!(key_from_name("NotAKey"), None);
+}
+
+#[test]
+fn test_config_overrides_only_listed_actions() {
+    let config = GameConfig::from_toml("[bindings]\nmove_left = [\"J\"]\n").unwrap();
+    assert_eq!(config.bindings.keys(Action::MoveLeft), &[KeyCode::J]);
+    assert_eq!(
+        config.bindings.keys(Action::MoveRight),
+        KeyBindings::default().keys(Action::MoveRight)
+    );
+}
+
+#[test]
+fn test_config_rejects_unknown_keys_and_conflicts() {
+    assert!(GameConfig::from_toml("[bindings]\npause = [\"Nope\"]\n").is_err());
+    assert!(GameConfig::from_toml("[bindings]\npause = [\"R\"]\nrestart = [\"R\"]\n").is_err());
+    assert!(GameConfig::from_toml("[bindings]\njump = [\"J\"]\n").is_err());
+}
+
+#[test]
+fn test_config_takes_default_keys_from_other_actions() {
+    let config = GameConfig::from_toml("[bindings]\nmove_left = [\"P\"]\n").unwrap();
+    assert_eq!(config.bindings.keys(Action::MoveLeft), &[KeyCode::P]);
+    assert_eq!(config.bindings.keys(Action::Pause), &[KeyCode::Space]);
+    assert_eq!(
+        config.bindings.action_for(KeyCode::P),
+        Some(Action::MoveLeft)
+    );
+    assert_eq!(config.bindings.conflict(), None);
+}
+
+#[test]
+fn test_rebind_takes_key_from_other_action() {
+    let mut bindings = KeyBindings::default();
+    bindings.rebind(Action::Restart, KeyCode::Space);
+    assert_eq!(bindings.keys(Action::Restart), &[KeyCode::Space]);
+    assert_eq!(bindings.keys(Action::Pause), &[KeyCode::P]);
+    assert_eq!(bindings.action_for(KeyCode::R), None);
+    assert_eq!(bindings.conflict(), None);
+    assert_eq!(bindings.describe(Action::Pause), "P");
+}
+
+#[test]
+fn test_bindings_round_trip_through_config() {
+    let mut config = GameConfig::default();
+    config.bindings.rebind(Action::MoveLeft, KeyCode::Comma);
+    assert_eq!(GameConfig::from_toml(&config.to_toml()).unwrap(), config);
+}
+
+#[test]
+fn test_bindings_do_not_change_gameplay_hash() {
+    let mut config = GameConfig::default();
+    let hash = config.gameplay_hash();
+    config.bindings.rebind(Action::Pause, KeyCode::Return);
+    assert_eq!(config.gameplay_hash(), hash);
+}
+
+#[test]
+fn test_menu_keys_are_bindable() {
+    let bindings = KeyBindings::default();
+    assert_eq!(bindings.action_for(KeyCode::W), Some(Action::MenuUp));
+    assert_eq!(bindings.action_for(KeyCode::Down), Some(Action::MenuDown));
+    assert_eq!(
+        bindings.action_for(KeyCode::Return),
+        Some(Action::MenuSelect)
+    );
+
+    // A left-handed layout moving the menu to the keypad
+    let config =
+        GameConfig::from_toml("[bindings]\nmenu_up = [\"Numpad8\"]\nmenu_down = [\"Numpad2\"]\n")
+            .unwrap();
+    assert_eq!(config.bindings.keys(Action::MenuUp), &[KeyCode::Numpad8]);
+    assert_eq!(config.bindings.action_for(KeyCode::Up), None);
+}
@@ -0,0 +1,97 @@
+use bounce_shield::{Brick, GameConfig, Input, SimEvent, Simulation, brick_area, brick_grid};
+
+fn config(rows: usize) -> GameConfig {
+    GameConfig {
+        brick_rows: rows,
+        ..GameConfig::default()
+    }
+}
+
+// Replace the grid with one brick just below the falling ball
+fn sim_with_brick(hp: u32) -> Simulation {
+    let mut sim = Simulation::new(config(1), 5);
+    let brick = Brick::new(sim.balls[0].x - 20.0, sim.balls[0].y + 40.0, 60.0, 20.0, hp);
+    sim.bricks = vec![brick];
+    sim
+}
+
+fn step_until(sim: &mut Simulation, wanted: impl Fn(&SimEvent) -> bool) -> Vec<SimEvent> {
+    let mut events = Vec::new();
+    for _ in 0..60 {
+        sim.step(Input::default());
+        events.extend(sim.drain_events());
+        if events.iter().any(&wanted) {
+            return events;
+        }
+    }
+    panic!("event never happened, got {:?}", events);
+}
+
+#[test]
+fn test_bricks_are_off_by_default() {
+    assert!(Simulation::new(GameConfig::default(), 1).bricks.is_empty());
+}
+
+#[test]
+fn test_grid_fits_its_area_without_overlaps() {
+    let config = config(5);
+    let area = brick_area(&config);
+    let bricks = brick_grid(&config, 1);
+    assert_eq!(bricks.len(), 5 * config.brick_columns);
+    for (i, brick) in bricks.iter().enumerate() {
+        assert!(brick.x >= area.x && brick.x + brick.width <= area.x + area.w);
+        assert!(brick.y >= area.y && brick.y + brick.height <= area.y + area.h);
+        for other in &bricks[i + 1..] {
+            assert!(!brick.aabb().overlaps(&other.aabb()));
+        }
+    }
+    // Clear of the ball's spawn height
+    assert!(area.y + area.h + config.ball_size <= config.height / 3.0);
+}
+
+#[test]
+fn test_top_rows_and_later_stages_are_tougher() {
+    let config = config(4);
+    let stage_one = brick_grid(&config, 1);
+    assert!(stage_one.first().unwrap().hp > stage_one.last().unwrap().hp);
+    let stage_two = brick_grid(&config, 2);
+    for (a, b) in stage_one.iter().zip(&stage_two) {
+        assert_eq!(b.hp, a.hp + 1);
+    }
+}
+
+#[test]
+fn test_ball_damages_and_bounces_off_brick() {
+    let mut sim = sim_with_brick(2);
+    step_until(&mut sim, |e| *e == SimEvent::BrickHit);
+    assert_eq!(sim.bricks[0].hp, 1);
+    assert!(sim.balls[0].dy < 0.0);
+    assert_eq!(sim.score, 0);
+}
+
+#[test]
+fn test_breaking_last_brick_scores_and_advances_stage() {
+    let mut sim = sim_with_brick(1);
+    let events = step_until(&mut sim, |e| matches!(e, SimEvent::StageCleared { .. }));
+    assert!(events.contains(&SimEvent::BrickDestroyed { score: 1 }));
+    assert!(events.contains(&SimEvent::StageCleared { next_stage: 2 }));
+    assert_eq!(sim.score, 1);
+    assert_eq!(sim.stage, 2);
+    assert_eq!(sim.bricks, brick_grid(&sim.config, 2));
+}
+
+#[test]
+fn test_reset_starts_again_from_stage_one() {
+    let mut sim = sim_with_brick(1);
+    step_until(&mut sim, |e| matches!(e, SimEvent::StageCleared { .. }));
+    sim.reset();
+    assert_eq!(sim.stage, 1);
+    assert_eq!(sim.bricks, brick_grid(&sim.config, 1));
+}
+
+#[test]
+fn test_grid_that_does_not_fit_is_rejected() {
+    assert!(GameConfig::from_toml("brick_rows = 200\n").is_err());
+    assert!(GameConfig::from_toml("brick_rows = 3\nbrick_columns = 0\n").is_err());
+    assert!(GameConfig::from_toml("brick_rows = 3\nbrick_columns = 12\n").is_ok());
+}
@@ -0,0 +1,86 @@
+use bounce_shield::{Camera, GameConfig, MAX_SHAKE_OFFSET, ZOOM_PULSE_SECS};
+use ggez::graphics::Rect;
+
+const SCREEN: Rect = Rect {
+    x: 0.0,
+    y: 0.0,
+    w: 1920.0,
+    h: 1080.0,
+};
+
+#[test]
+fn test_still_camera_shows_the_whole_screen() {
+    let mut camera = Camera::new(true);
+    camera.update(0.016);
+    assert_eq!(camera.view(SCREEN), SCREEN);
+}
+
+#[test]
+fn test_trauma_shakes_then_settles() {
+    let mut camera = Camera::new(true);
+    camera.add_trauma(0.7);
+    camera.add_trauma(0.7);
+    assert_eq!(camera.trauma(), 1.0);
+
+    let mut moved = false;
+    for _ in 0..10 {
+        camera.update(0.016);
+        let [x, y] = camera.offset();
+        assert!(x.abs() <= MAX_SHAKE_OFFSET && y.abs() <= MAX_SHAKE_OFFSET);
+        moved |= camera.view(SCREEN) != SCREEN;
+    }
+    assert!(moved);
+
+    camera.update(1.0);
+    assert_eq!(camera.trauma(), 0.0);
+    assert_eq!(camera.view(SCREEN), SCREEN);
+}
+
+#[test]
+fn test_zoom_pulse_swells_and_ends() {
+    let mut camera = Camera::new(true);
+    camera.pulse_zoom();
+    camera.update(ZOOM_PULSE_SECS / 2.0);
+    assert!(camera.zoom() > 1.0);
+    let view = camera.view(SCREEN);
+    assert!(view.w < SCREEN.w);
+    // Zooms about the middle
+    assert!((view.x + view.w / 2.0 - SCREEN.w / 2.0).abs() < MAX_SHAKE_OFFSET);
+
+    camera.update(ZOOM_PULSE_SECS);
+    assert_eq!(camera.zoom(), 1.0);
+}
+
+#[test]
+fn test_disabled_camera_never_moves() {
+    let mut camera = Camera::new(false);
+    camera.add_trauma(1.0);
+    camera.pulse_zoom();
+    camera.update(0.016);
+    assert_eq!(camera.view(SCREEN), SCREEN);
+}
+
+#[test]
+fn test_screen_shake_setting() {
+    assert!(GameConfig::default().screen_shake);
+    let still = GameConfig::from_toml("screen_shake = false\n").unwrap();
+    assert!(!still.screen_shake);
+    // Only changes how the game looks, so replays still match
+    assert_eq!(still.gameplay_hash(), GameConfig::default().gameplay_hash());
+}
+
+#[test]
+fn test_view_point_follows_the_zoom() {
+    let mut camera = Camera::new(true);
+    assert_eq!(camera.view_point(SCREEN, [100.0, 50.0]), [100.0, 50.0]);
+
+    camera.pulse_zoom();
+    camera.update(ZOOM_PULSE_SECS / 2.0);
+    let view = camera.view(SCREEN);
+    assert_eq!(camera.view_point(SCREEN, [0.0, 0.0]), [view.x, view.y]);
+    // The window's far corner shows the far corner of the zoomed view
+    let [x, y] = camera.view_point(SCREEN, [SCREEN.w, SCREEN.h]);
+    assert!((x - (view.x + view.w)).abs() < 0.01);
+    assert!((y - (view.y + view.h)).abs() < 0.01);
+    assert!(x < SCREEN.w);
+}
@@ -0,0 +1,87 @@
+use bounce_shield::{Ball, Bar, ConfigError, GameConfig, Simulation};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+#[test]
+fn test_partial_file_keeps_defaults() {
+    let config = GameConfig::from_toml("ball_speed = 450.0\ninitial_hearts = 5\n").unwrap();
+    assert_eq!(config.ball_speed, 450.0);
+    assert_eq!(config.initial_hearts, 5);
+    assert_eq!(config.width, GameConfig::default().width);
+}
+
+#[test]
+fn test_round_trip() {
+    let config = GameConfig {
+        bar_width: 300.0,
+        ..GameConfig::default()
+    };
+    assert_eq!(GameConfig::from_toml(&config.to_toml()).unwrap(), config);
+}
+
+#[test]
+fn test_shipped_config_file_is_valid() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("bounce_shield.toml");
+    assert_eq!(GameConfig::load(&path).unwrap(), GameConfig::default());
+}
+
+#[test]
+fn test_rejects_impossible_values() {
+    let invalid = [
+        "bar_width = 5000.0",
+        "ball_speed = -1.0",
+        "initial_hearts = 0",
+        "max_bounce_angle = 90.0",
+        "height = 40.0",
+        "ball_size = 1000.0",
+    ];
+    for text in invalid {
+        assert!(
+            matches!(GameConfig::from_toml(text), Err(ConfigError::Invalid(_))),
+            "{} should be rejected",
+            text
+        );
+    }
+}
+
+#[test]
+fn test_rejects_malformed_files() {
+    assert!(matches!(
+        GameConfig::from_toml("ball_speed = \"fast\""),
+        Err(ConfigError::Parse(_))
+    ));
+    assert!(matches!(
+        GameConfig::from_toml("bal_speed = 10.0"),
+        Err(ConfigError::Parse(_))
+    ));
+    assert!(matches!(
+        GameConfig::load(std::path::Path::new("no/such/config.toml")),
+        Err(ConfigError::Io(..))
+    ));
+}
+
+#[test]
+fn test_config_reaches_ball_bar_and_simulation() {
+    let config = GameConfig {
+        width: 800.0,
+        height: 600.0,
+        ball_size: 10.0,
+        ball_speed: 100.0,
+        bar_width: 80.0,
+        initial_hearts: 7,
+        ..GameConfig::default()
+    };
+
+    let ball = Ball::new(&config, &mut StdRng::seed_from_u64(1));
+    assert!(ball.x >= 10.0 && ball.x <= 790.0);
+    assert_eq!(ball.y, 200.0);
+    assert_eq!(ball.dx.abs(), 100.0);
+
+    let mut bar = Bar::new(&config);
+    assert_eq!(bar.x, 360.0);
+    bar.move_right(10.0);
+    assert_eq!(bar.x, 720.0);
+
+    let sim = Simulation::new(config, 1);
+    assert_eq!(sim.hearts, 7);
+}
@@ -0,0 +1,20 @@
+mod common;
+
+use bounce_shield::{load_or_default, write_atomically};
+use common::TempDir;
+
+#[test]
+fn test_write_then_load() {
+    let dir = TempDir::new();
+    let path = dir.join("nested").join("value.txt");
+    let parse = |text: &str| text.trim().parse::<u32>().ok();
+
+    // Missing and corrupt files both mean the default
+    assert_eq!(load_or_default(&path, "test", parse), 0);
+    write_atomically(&path, "not a number").unwrap();
+    assert_eq!(load_or_default(&path, "test", parse), 0);
+
+    write_atomically(&path, "42\n").unwrap();
+    assert_eq!(load_or_default(&path, "test", parse), 42);
+    assert!(!path.with_extension("tmp").exists());
+}
@@ -0,0 +1,27 @@
+mod common;
+
+use bounce_shield::DisplaySettings;
+use common::TempDir;
+
+#[test]
+fn test_parse_defaults_missing_fields() {
+    assert_eq!(DisplaySettings::parse(""), Some(DisplaySettings::default()));
+    assert!(DisplaySettings::default().screen_shake);
+    let settings = DisplaySettings::parse("screen_shake = false\n").unwrap();
+    assert!(!settings.screen_shake);
+    assert_eq!(DisplaySettings::parse("screen_shake = \"yes\""), None);
+}
+
+#[test]
+fn test_save_and_load_round_trip() {
+    let dir = TempDir::new();
+    let path = dir.join("display_settings.toml");
+    let settings = DisplaySettings {
+        screen_shake: false,
+    };
+    settings.save(&path).unwrap();
+    assert_eq!(DisplaySettings::load(&path), settings);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(DisplaySettings::load(&path), DisplaySettings::default());
+}
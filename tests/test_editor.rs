@@ -0,0 +1,146 @@
+mod common;
+
+use bounce_shield::{
+    BALL_SPEED_STEP, Cell, EDITOR_COLUMNS, EDITOR_ROWS, Editor, GameConfig, Level, grid_cell,
+};
+use common::TempDir;
+use std::path::Path;
+
+fn blank(dir: &Path) -> Editor {
+    Editor::open(&dir.join("missing.txt"), &GameConfig::default()).unwrap()
+}
+
+#[test]
+fn test_missing_file_opens_blank_grid() {
+    let dir = TempDir::new();
+    let editor = blank(&dir);
+    assert_eq!(editor.rows(), EDITOR_ROWS);
+    assert_eq!(editor.columns(), EDITOR_COLUMNS);
+    assert!(
+        editor
+            .level
+            .layout
+            .iter()
+            .flatten()
+            .all(|&c| c == Cell::Empty)
+    );
+}
+
+#[test]
+fn test_small_levels_keep_their_size_on_the_editor_grid() {
+    let level = Level::parse("layout:\n1#\n", &GameConfig::default()).unwrap();
+    let dir = TempDir::new();
+    let mut editor = Editor::new(level.clone(), dir.join("small.txt"));
+    assert_eq!(editor.rows(), EDITOR_ROWS);
+    assert_eq!(editor.columns(), EDITOR_COLUMNS);
+    assert_eq!(editor.level, level);
+    assert_eq!(editor.cell(0, 1), Cell::Obstacle);
+    assert_eq!(editor.cell(0, 2), Cell::Empty);
+
+    // Erasing outside the level leaves it alone, painting grows it
+    editor.erase(3, 4);
+    assert_eq!(editor.level, level);
+    editor.paint(2, 3);
+    assert_eq!(editor.level.layout.len(), 3);
+    assert_eq!(editor.level.columns(), 4);
+    assert_eq!(editor.cell(2, 3), Cell::Brick(1));
+}
+
+#[test]
+fn test_cell_at_finds_the_cell_under_the_mouse() {
+    let config = GameConfig::default();
+    let dir = TempDir::new();
+    let editor = blank(&dir);
+    let cell = grid_cell(&config, EDITOR_ROWS, EDITOR_COLUMNS, 2, 5);
+    let (x, y) = (cell.x + cell.w / 2.0, cell.y + cell.h / 2.0);
+    assert_eq!(editor.cell_at(&config, x, y), Some((2, 5)));
+    // The gaps between cells and the rest of the field aren't cells
+    assert_eq!(editor.cell_at(&config, cell.x - 1.0, y), None);
+    assert_eq!(editor.cell_at(&config, x, config.height - 10.0), None);
+}
+
+#[test]
+fn test_paint_and_erase() {
+    let dir = TempDir::new();
+    let mut editor = blank(&dir);
+    editor.brush = Cell::Brick(3);
+    editor.paint(0, 0);
+    editor.brush = Cell::Obstacle;
+    editor.paint(0, 1);
+    assert_eq!(
+        editor.level.layout[0][..2],
+        [Cell::Brick(3), Cell::Obstacle]
+    );
+    editor.erase(0, 0);
+    assert_eq!(editor.level.layout[0][0], Cell::Empty);
+}
+
+#[test]
+fn test_parameters_start_from_config() {
+    let config = GameConfig::default();
+    let dir = TempDir::new();
+    let mut editor = blank(&dir);
+    assert_eq!(editor.level.ball_speed, None);
+    editor.adjust_ball_speed(&config, BALL_SPEED_STEP);
+    assert_eq!(
+        editor.ball_speed(&config),
+        config.ball_speed + BALL_SPEED_STEP
+    );
+    editor.adjust_hearts(&config, 1);
+    assert_eq!(editor.level.hearts, Some(config.initial_hearts + 1));
+    // Never down to zero hearts or a bar wider than the field
+    editor.adjust_hearts(&config, -100);
+    assert_eq!(editor.level.hearts, Some(1));
+    editor.adjust_bar_width(&config, 10_000.0);
+    assert_eq!(editor.bar_width(&config), config.width);
+}
+
+#[test]
+fn test_empty_level_is_not_playable() {
+    let dir = TempDir::new();
+    let editor = blank(&dir);
+    let config = GameConfig::default();
+    assert!(editor.playable(&config).is_err());
+    assert!(editor.save(&config).is_err());
+    assert!(!editor.path.exists());
+}
+
+#[test]
+fn test_saved_level_loads_back() {
+    let config = GameConfig::default();
+    let dir = TempDir::new();
+    let path = dir.join("saved.txt");
+    let mut editor = Editor::open(&path, &config).unwrap();
+    editor.paint(1, 2);
+    editor.brush = Cell::Obstacle;
+    editor.paint(1, 3);
+    editor.adjust_ball_speed(&config, -BALL_SPEED_STEP);
+    editor.save(&config).unwrap();
+
+    let loaded = Level::load(&path, &config).unwrap();
+    assert_eq!(loaded, editor.playable(&config).unwrap());
+    assert_eq!(loaded.bricks(&config, 0).len(), 1);
+    assert_eq!(loaded.obstacles(&config).len(), 1);
+    // Reopening picks up where the last session left off
+    let reopened = Editor::open(&path, &config).unwrap();
+    assert_eq!(reopened.level, loaded);
+}
+
+#[test]
+fn test_levels_play_on_the_grid_they_were_drawn_on() {
+    let config = GameConfig::default();
+    let dir = TempDir::new();
+    let mut editor = blank(&dir);
+    editor.paint(0, 0);
+    editor.paint(2, 5);
+    let level = editor.playable(&config).unwrap();
+    let bricks = level.bricks(&config, 0);
+    assert_eq!(bricks.len(), 2);
+    for (brick, (row, column)) in bricks.iter().zip([(0, 0), (2, 5)]) {
+        let area = editor.cell_area(&config, row, column);
+        assert_eq!(
+            (brick.x, brick.y, brick.width, brick.height),
+            (area.x, area.y, area.w, area.h)
+        );
+    }
+}
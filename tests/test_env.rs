@@ -0,0 +1,101 @@
+use bounce_shield::env::{Action, Env, HEART_LOST_PENALTY, Observation};
+use bounce_shield::{GameConfig, GameState};
+
+fn env() -> Env {
+    Env::new(GameConfig::default())
+}
+
+#[test]
+fn test_actions_by_index() {
+    assert_eq!(Action::from_index(0), Some(Action::Stay));
+    assert_eq!(Action::from_index(2), Some(Action::Right));
+    assert_eq!(Action::from_index(3), None);
+    assert!(Action::Left.input().left);
+    assert!(!Action::Stay.input().is_steering());
+}
+
+#[test]
+fn test_reset_is_reproducible() {
+    let mut env = env();
+    let first = env.reset(42);
+    env.step(Action::Left);
+    assert_eq!(env.reset(42), first);
+    assert_eq!(first.hearts, env.sim.config.initial_hearts);
+    assert_eq!(first.score, 0);
+
+    let mut other = self::env();
+    other.reset(42);
+    for action in [Action::Left, Action::Right, Action::Stay].repeat(100) {
+        assert_eq!(env.step(action), other.step(action));
+    }
+}
+
+#[test]
+fn test_actions_move_the_bar() {
+    let mut env = env();
+    let start = env.reset(1).bar_x;
+    let (observation, _, _) = env.step(Action::Left);
+    assert!(observation.bar_x < start);
+    let (observation, _, _) = env.step(Action::Right);
+    assert_eq!(observation.bar_x, start);
+}
+
+#[test]
+fn test_returns_are_rewarded_and_misses_penalised() {
+    let mut env = env();
+    env.reset(1);
+    let (bar_x, bar_y, bar_width) = (env.sim.bar.x, env.sim.bar.y, env.sim.bar.width);
+    let ball = &mut env.sim.balls[0];
+    ball.x = bar_x + (bar_width - ball.size) / 2.0;
+    ball.y = bar_y - ball.size - 1.0;
+    ball.dx = 0.0;
+    ball.dy = 300.0;
+    let (_, reward, done) = env.step(Action::Stay);
+    assert_eq!(reward, 1.0);
+    assert!(!done);
+
+    env.sim.balls[0].y = env.sim.config.height + 1.0;
+    env.sim.balls[0].dy = 300.0;
+    let (observation, reward, _) = env.step(Action::Stay);
+    assert_eq!(reward, -HEART_LOST_PENALTY);
+    assert_eq!(observation.hearts, env.sim.config.initial_hearts - 1);
+}
+
+#[test]
+fn test_episode_ends_and_stays_done() {
+    let mut env = env();
+    env.reset(3);
+    env.sim.hearts = 1;
+    env.sim.balls[0].y = env.sim.config.height + 1.0;
+    let (observation, reward, done) = env.step(Action::Stay);
+    assert!(done);
+    assert_eq!(reward, -HEART_LOST_PENALTY);
+    assert_eq!(env.sim.state, GameState::GameOver);
+    assert_eq!(env.step(Action::Left), (observation, 0.0, true));
+}
+
+#[test]
+fn test_observation_array_is_scaled() {
+    let mut env = env();
+    let observation = env.reset(5);
+    let array = observation.to_array(&env.sim.config);
+    assert_eq!(array.len(), Observation::LEN);
+    assert!(array.iter().all(|value| value.abs() <= 1.5));
+}
+
+#[test]
+fn test_long_runs_do_not_pile_up_events() {
+    let mut env = env();
+    env.ticks_per_step = 4;
+    env.reset(9);
+    let mut episodes = 0;
+    for step in 0..50_000u32 {
+        let action = Action::from_index(step as usize / 7 % 3).unwrap();
+        if env.step(action).2 {
+            env.reset(step as u64);
+            episodes += 1;
+        }
+        assert!(env.sim.drain_events().is_empty());
+    }
+    assert!(episodes > 0);
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use common::TempDir;
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Killed when dropped, so a failed assertion doesn't leave a game running
+struct Process(Child);
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn spawn(dir: &Path, args: &[&str]) -> Process {
+    let child = Command::new(env!("CARGO_BIN_EXE_bounce_shield"))
+        .arg("--headless")
+        .args(args)
+        .current_dir(dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    Process(child)
+}
+
+// Everything the game prints, once it exits by itself within the time limit
+fn finish(process: &mut Process, mut stdout: impl Read) -> String {
+    let start = Instant::now();
+    while process.0.try_wait().unwrap().is_none() {
+        assert!(
+            start.elapsed() < Duration::from_secs(60),
+            "headless game didn't finish"
+        );
+        thread::sleep(Duration::from_millis(50));
+    }
+    assert!(process.0.wait().unwrap().success());
+    let mut output = String::new();
+    stdout.read_to_string(&mut output).unwrap();
+    output
+}
+
+fn result_line(output: &str) -> &str {
+    output
+        .lines()
+        .find(|line| line.starts_with("result: "))
+        .unwrap_or_else(|| panic!("no result in {:?}", output))
+}
+
+#[test]
+fn test_host_and_client_play_a_match_as_two_processes() {
+    let dir = TempDir::new();
+    // Both read the same config from the working directory, or the host turns the client away
+    fs::write(
+        dir.join("bounce_shield.toml"),
+        "initial_hearts = 1\nball_speed = 900\n",
+    )
+    .unwrap();
+
+    let mut host = spawn(&dir, &["--host", "127.0.0.1:0"]);
+    let mut host_out = BufReader::new(host.0.stdout.take().unwrap());
+    let mut line = String::new();
+    let addr = loop {
+        line.clear();
+        assert!(host_out.read_line(&mut line).unwrap() > 0, "host exited");
+        if let Some(addr) = line.trim().strip_prefix("hosting on ") {
+            break addr.to_string();
+        }
+    };
+
+    let mut client = spawn(&dir, &["--join", &addr]);
+    let client_out = client.0.stdout.take().unwrap();
+    let client_output = finish(&mut client, client_out);
+    let host_output = finish(&mut host, host_out);
+
+    assert!(result_line(&host_output).ends_with(" wins"));
+    assert_eq!(result_line(&host_output), result_line(&client_output));
+    // The host ran the match, so it keeps the replay
+    assert!(host_output.contains("replay saved to"));
+}
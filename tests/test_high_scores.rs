@@ -0,0 +1,63 @@
+mod common;
+
+use std::fs;
+
+use bounce_shield::{HighScores, MAX_HIGH_SCORES};
+use common::TempDir;
+
+#[test]
+fn test_record_keeps_best_scores_in_order() {
+    let mut scores = HighScores::default();
+    assert!(scores.record(5));
+    assert!(scores.record(12));
+    assert!(scores.record(7));
+    assert!(!scores.record(0));
+    assert_eq!(scores.scores, vec![12, 7, 5]);
+    assert_eq!(scores.best(), 12);
+
+    for score in 20..40 {
+        scores.record(score);
+    }
+    assert_eq!(scores.scores.len(), MAX_HIGH_SCORES);
+    assert!(!scores.record(1));
+}
+
+#[test]
+fn test_save_and_load_round_trip() {
+    let dir = TempDir::new();
+    let path = dir.join("round_trip.txt");
+    let mut scores = HighScores::default();
+    scores.record(42);
+    scores.record(17);
+
+    scores.save(&path).unwrap();
+
+    assert_eq!(HighScores::load(&path), scores);
+    assert!(!path.with_extension("tmp").exists());
+}
+
+#[test]
+fn test_missing_or_corrupt_files_load_empty() {
+    let dir = TempDir::new();
+    assert_eq!(
+        HighScores::load(&dir.join("does_not_exist.txt")),
+        HighScores::default()
+    );
+
+    let garbage = dir.join("garbage.txt");
+    fs::write(&garbage, "\u{1F3D0} not a score file").unwrap();
+    assert_eq!(HighScores::load(&garbage), HighScores::default());
+
+    // Right header, damaged body
+    let truncated = dir.join("truncated.txt");
+    fs::write(&truncated, "bounce_shield high scores v1\n12\nseven\n").unwrap();
+    assert_eq!(HighScores::load(&truncated), HighScores::default());
+}
+
+#[test]
+fn test_unknown_version_is_ignored() {
+    assert_eq!(
+        HighScores::parse("bounce_shield high scores v99\n12\n"),
+        None
+    );
+}
@@ -0,0 +1,148 @@
+use bounce_shield::{Cell, GameConfig, Level, LevelError, SimEvent, Simulation, load_levels};
+use std::path::Path;
+
+const LEVEL: &str = "\
+# a comment
+name = Test
+ball_speed = 450
+bar_width = 200
+hearts = 5
+layout:
+.1#
+22.
+";
+
+fn parse(text: &str) -> Result<Level, (usize, String)> {
+    Level::parse(text, &GameConfig::default()).map_err(|e| (e.line, e.message))
+}
+
+fn with_levels(levels: Vec<Level>) -> Simulation {
+    let config = GameConfig {
+        levels,
+        ..GameConfig::default()
+    };
+    Simulation::new(config, 1)
+}
+
+#[test]
+fn test_parse_settings_and_layout() {
+    let level = parse(LEVEL).unwrap();
+    assert_eq!(level.name, "Test");
+    assert_eq!(level.ball_speed, Some(450.0));
+    assert_eq!(level.bar_width, Some(200.0));
+    assert_eq!(level.hearts, Some(5));
+    assert_eq!(
+        level.layout,
+        vec![
+            vec![Cell::Empty, Cell::Brick(1), Cell::Obstacle],
+            vec![Cell::Brick(2), Cell::Brick(2), Cell::Empty],
+        ]
+    );
+}
+
+#[test]
+fn test_parse_errors_point_at_the_line() {
+    let bad_setting = LEVEL.replace("hearts = 5", "lives = 5");
+    assert_eq!(parse(&bad_setting).unwrap_err().0, 5);
+
+    let bad_number = LEVEL.replace("ball_speed = 450", "ball_speed = fast");
+    assert_eq!(parse(&bad_number).unwrap_err().0, 3);
+
+    let bad_cell = LEVEL.replace("22.", "2x.");
+    let (line, message) = parse(&bad_cell).unwrap_err();
+    assert_eq!(line, 8);
+    assert!(message.contains("column 2"), "{}", message);
+
+    let too_wide = LEVEL.replace("bar_width = 200", "bar_width = 5000");
+    assert_eq!(parse(&too_wide).unwrap_err().0, 4);
+
+    assert_eq!(
+        parse("name = x\n").unwrap_err().1,
+        "missing 'layout:' section"
+    );
+    assert_eq!(parse("layout:\n.#.\n").unwrap_err().0, 1);
+}
+
+#[test]
+fn test_blank_rows_only_allowed_after_the_layout() {
+    let gap = LEVEL.replace(".1#\n22.", ".1#\n   \n22.");
+    let (line, message) = parse(&gap).unwrap_err();
+    assert_eq!(line, 8);
+    assert!(message.contains("blank row"), "{}", message);
+
+    let trailing = format!("{}\n\n", LEVEL);
+    assert_eq!(parse(&trailing).unwrap().layout.len(), 2);
+}
+
+#[test]
+fn test_error_message_names_file_and_line() {
+    let error = LevelError::Parse(
+        Path::new("levels/01.txt").to_path_buf(),
+        Level::parse("oops\nlayout:\n1\n", &GameConfig::default()).unwrap_err(),
+    );
+    assert!(error.to_string().starts_with("levels/01.txt:1: "));
+}
+
+#[test]
+fn test_shipped_levels_load_in_order() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("levels");
+    let levels = load_levels(&dir, &GameConfig::default()).unwrap();
+    let names: Vec<&str> = levels.iter().map(|level| level.name.as_str()).collect();
+    assert_eq!(names, ["Warm Up", "Pillars", "Fortress"]);
+}
+
+#[test]
+fn test_missing_levels_directory_means_no_levels() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("no_such_levels");
+    assert!(
+        load_levels(&dir, &GameConfig::default())
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_level_sets_up_the_run() {
+    let level = parse(LEVEL).unwrap();
+    let sim = with_levels(vec![level]);
+    assert_eq!(sim.hearts, 5);
+    assert_eq!(sim.bar.width, 200.0);
+    assert_eq!(sim.balls[0].dy, 450.0);
+    assert_eq!(sim.bricks.len(), 3);
+    assert_eq!(sim.obstacles.len(), 1);
+    assert_eq!(sim.bricks.iter().map(|b| b.hp).sum::<u32>(), 5);
+}
+
+#[test]
+fn test_clearing_a_level_moves_to_the_next_and_wraps() {
+    let first = parse(LEVEL).unwrap();
+    let second = parse("name = Two\nhearts = 7\nlayout:\n1\n").unwrap();
+    let mut sim = with_levels(vec![first, second]);
+
+    sim.bricks.clear();
+    sim.step(Default::default());
+    assert!(
+        sim.drain_events()
+            .contains(&SimEvent::StageCleared { next_stage: 2 })
+    );
+    assert_eq!(sim.current_level().unwrap().name, "Two");
+    assert_eq!(sim.hearts, 7);
+    assert_eq!(sim.bar.width, sim.config.bar_width);
+    assert!(sim.obstacles.is_empty());
+
+    // Back to the first level, one hit point tougher
+    sim.bricks.clear();
+    sim.step(Default::default());
+    assert_eq!(sim.current_level().unwrap().name, "Test");
+    assert_eq!(sim.bricks.iter().map(|b| b.hp).sum::<u32>(), 8);
+}
+
+#[test]
+fn test_levels_change_the_replay_fingerprint() {
+    let plain = GameConfig::default();
+    let with = GameConfig {
+        levels: vec![parse(LEVEL).unwrap()],
+        ..GameConfig::default()
+    };
+    assert_ne!(plain.gameplay_hash(), with.gameplay_hash());
+}
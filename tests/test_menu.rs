@@ -0,0 +1,98 @@
+mod common;
+
+use bounce_shield::{
+    Channel, Game, GameConfig, GameState, Input, Menu, MenuItem, MenuPage, REPLAY_DIR, TICK_DT,
+};
+use common::TempDir;
+use std::fs;
+use std::time::Duration;
+
+#[test]
+fn test_main_page_lists_the_entries() {
+    assert_eq!(
+        MenuPage::Main.items(),
+        &[
+            MenuItem::Play,
+            MenuItem::Modes,
+            MenuItem::Settings,
+            MenuItem::HighScores,
+            MenuItem::Quit,
+        ]
+    );
+    let menu = Menu::default();
+    assert_eq!(menu.page, MenuPage::Main);
+    assert_eq!(menu.selected_item(), MenuItem::Play);
+}
+
+#[test]
+fn test_navigation_wraps_around() {
+    let mut menu = Menu::default();
+    menu.move_up();
+    assert_eq!(menu.selected_item(), MenuItem::Quit);
+    menu.move_down();
+    assert_eq!(menu.selected_item(), MenuItem::Play);
+    menu.move_down();
+    assert_eq!(menu.selected_item(), MenuItem::Modes);
+}
+
+#[test]
+fn test_confirm_opens_pages_and_returns_actions() {
+    let mut menu = Menu::default();
+    menu.move_down();
+    assert_eq!(menu.confirm(), MenuItem::Modes);
+    assert_eq!(menu.page, MenuPage::Modes);
+    assert_eq!(menu.selected_item(), MenuItem::OnePlayer);
+
+    // Entries that don't change page leave the menu where it is
+    menu.move_down();
+    assert_eq!(menu.confirm(), MenuItem::TwoPlayers);
+    assert_eq!(menu.page, MenuPage::Modes);
+}
+
+#[test]
+fn test_back_highlights_the_entry_that_led_away() {
+    let mut menu = Menu::default();
+    menu.move_down();
+    menu.move_down();
+    menu.confirm();
+    assert_eq!(menu.page, MenuPage::Settings);
+
+    menu.move_up();
+    assert_eq!(menu.selected_item(), MenuItem::Back);
+    assert_eq!(menu.confirm(), MenuItem::Back);
+    assert_eq!(menu.page, MenuPage::Main);
+    assert_eq!(menu.selected_item(), MenuItem::Settings);
+
+    assert!(!menu.back());
+    assert_eq!(menu.page, MenuPage::Main);
+}
+
+#[test]
+fn test_settings_has_a_volume_for_each_channel() {
+    let items = MenuPage::Settings.items();
+    for channel in [Channel::Master, Channel::Music, Channel::Effects] {
+        assert!(items.contains(&MenuItem::Volume(channel)));
+    }
+    assert_eq!(MenuItem::Volume(Channel::Music).label(), "Music Volume");
+}
+
+#[test]
+fn test_switching_mode_from_the_menu_saves_no_replay() {
+    let dir = TempDir::new();
+    let mut game = Game::headless(GameConfig::default(), 5, &dir);
+    game.sim.state = GameState::Playing;
+    for _ in 0..30 {
+        game.frame(
+            Duration::from_secs_f32(TICK_DT),
+            (Input::default(), Input::default()),
+        );
+    }
+    // Leaving for the menu saves the run as far as it got
+    game.open_menu();
+    assert!(dir.join(REPLAY_DIR).exists());
+    fs::remove_dir_all(dir.join(REPLAY_DIR)).unwrap();
+
+    game.start_mode(true);
+    assert!(game.sim.config.two_player);
+    assert!(!dir.join(REPLAY_DIR).exists());
+}
@@ -0,0 +1,328 @@
+use bounce_shield::{
+    ClientSession, Connection, GameConfig, GameState, HostEvent, HostSession, Input, Interpolator,
+    MAX_FRAME, Message, NetError, PROTOCOL_VERSION, Player, PowerUpType, SimEvent, Simulation,
+    Snapshot, Surface,
+};
+use std::io::Write;
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn match_config() -> GameConfig {
+    GameConfig {
+        two_player: true,
+        ..GameConfig::default()
+    }
+}
+
+fn snapshot() -> Snapshot {
+    let mut sim = Simulation::new(match_config(), 4);
+    sim.spawn_extra_balls(2);
+    sim.score = 7;
+    Snapshot::capture(
+        &sim,
+        2,
+        vec![
+            SimEvent::BarHit,
+            SimEvent::HeartLost {
+                player: Player::Two,
+                remaining: 2,
+            },
+            SimEvent::PowerUpCollected(PowerUpType::MultiBall),
+            SimEvent::Impact {
+                x: 0.0,
+                y: 310.5,
+                normal: [1.0, 0.0],
+                surface: Surface::Wall,
+            },
+            SimEvent::BallLost {
+                x: 640.0,
+                y: 1080.0,
+            },
+        ],
+    )
+}
+
+// Keep polling until `done` holds, failing after a couple of seconds
+fn wait_until(mut done: impl FnMut() -> bool) {
+    let start = Instant::now();
+    while !done() {
+        assert!(
+            start.elapsed() < Duration::from_secs(2),
+            "timed out waiting on localhost"
+        );
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn test_messages_round_trip() {
+    let messages = [
+        Message::Hello {
+            version: PROTOCOL_VERSION,
+            config_hash: 0x1234_5678_9abc_def0,
+        },
+        Message::Welcome,
+        Message::Reject("full".to_string()),
+        Message::Input(Input {
+            left: true,
+            right: false,
+            axis: -90,
+            target: Some(640),
+        }),
+        Message::Input(Input::default()),
+        Message::Snapshot(snapshot()),
+    ];
+    for message in messages {
+        assert_eq!(Message::from_bytes(&message.to_bytes()).unwrap(), message);
+    }
+}
+
+#[test]
+fn test_bad_messages_are_rejected() {
+    let bytes = Message::Snapshot(snapshot()).to_bytes();
+    assert!(matches!(
+        Message::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(NetError::Protocol(_))
+    ));
+    assert!(Message::from_bytes(&[]).is_err());
+    assert!(Message::from_bytes(&[99]).is_err());
+    assert!(Message::from_bytes(&[1, 0]).is_err());
+}
+
+#[test]
+fn test_snapshot_applies_to_a_fresh_simulation() {
+    let snapshot = snapshot();
+    let mut sim = Simulation::new(match_config(), 99);
+    snapshot.apply(&mut sim);
+    assert_eq!(
+        Snapshot::capture(&sim, 2, snapshot.events.clone()),
+        snapshot
+    );
+}
+
+#[test]
+fn test_interpolator_blends_between_snapshots() {
+    let mut interpolator = Interpolator::default();
+    let start = Instant::now();
+    assert_eq!(interpolator.sample(start), None);
+
+    let first = snapshot();
+    let mut second = first.clone();
+    second.balls[0][0] += 100.0;
+    second.bar[0] += 40.0;
+    interpolator.push(start, first.clone());
+    assert_eq!(interpolator.sample(start), Some(first.clone()));
+
+    let later = start + Duration::from_millis(20);
+    interpolator.push(later, second.clone());
+    let halfway = interpolator
+        .sample(later + Duration::from_millis(10))
+        .unwrap();
+    assert_eq!(halfway.balls[0][0], first.balls[0][0] + 50.0);
+    assert_eq!(halfway.bar[0], first.bar[0] + 20.0);
+    // Never runs past the latest snapshot
+    assert_eq!(
+        interpolator.sample(later + Duration::from_secs(1)),
+        Some(second)
+    );
+}
+
+#[test]
+fn test_loopback_match() {
+    let config = match_config();
+    let mut host = HostSession::bind("127.0.0.1:0", &config).unwrap();
+    let mut client = ClientSession::connect(host.local_addr().unwrap(), &config).unwrap();
+
+    let mut joined = Vec::new();
+    wait_until(|| {
+        joined.extend(host.poll());
+        host.is_connected()
+    });
+    assert_eq!(joined, vec![HostEvent::Joined]);
+    wait_until(|| {
+        client.poll(Instant::now()).unwrap();
+        client.is_welcomed()
+    });
+
+    // Player two's input reaches the host's simulation
+    let left = Input {
+        left: true,
+        ..Input::default()
+    };
+    client.send_input(left).unwrap();
+    wait_until(|| {
+        host.poll();
+        host.remote_input() == left
+    });
+    let mut sim = Simulation::new(config.clone(), 5);
+    let top = sim.player_two.as_ref().unwrap().bar.x;
+    sim.step_players(Input::default(), host.remote_input());
+    assert!(sim.player_two.as_ref().unwrap().bar.x < top);
+
+    // And the host's state and events reach the client
+    sim.state = GameState::Countdown;
+    assert!(
+        host.send_snapshot(Snapshot::capture(&sim, 3, vec![SimEvent::BarHit]))
+            .is_empty()
+    );
+    let mut events = Vec::new();
+    wait_until(|| {
+        events.extend(client.poll(Instant::now()).unwrap());
+        client.snapshot(Instant::now()).is_some()
+    });
+    assert_eq!(events, vec![SimEvent::BarHit]);
+    let mut view = Simulation::new(config, 6);
+    client.snapshot(Instant::now()).unwrap().apply(&mut view);
+    assert_eq!(view.state, GameState::Countdown);
+    assert_eq!(
+        view.player_two.as_ref().unwrap().bar.x,
+        sim.player_two.as_ref().unwrap().bar.x
+    );
+
+    // Closing the client frees the host for the next player
+    drop(client);
+    let mut left_events = Vec::new();
+    wait_until(|| {
+        left_events.extend(host.poll());
+        !left_events.is_empty()
+    });
+    assert!(matches!(left_events[0], HostEvent::Left(_)));
+    assert!(!host.is_connected());
+    assert_eq!(host.remote_input(), Input::default());
+}
+
+#[test]
+fn test_host_drops_peers_that_never_say_hello() {
+    let config = match_config();
+    let mut host = HostSession::bind("127.0.0.1:0", &config).unwrap();
+    host.handshake_timeout = Duration::from_millis(100);
+    // Takes the only slot until it times out
+    let _silent = TcpStream::connect(host.local_addr().unwrap()).unwrap();
+    thread::sleep(Duration::from_millis(20));
+    host.poll();
+    thread::sleep(Duration::from_millis(120));
+    let mut client = ClientSession::connect(host.local_addr().unwrap(), &config).unwrap();
+    wait_until(|| {
+        host.poll();
+        host.is_connected()
+    });
+    wait_until(|| {
+        client.poll(Instant::now()).unwrap();
+        client.is_welcomed()
+    });
+}
+
+#[test]
+fn test_host_drops_a_welcomed_peer_that_goes_quiet() {
+    let config = match_config();
+    let mut host = HostSession::bind("127.0.0.1:0", &config).unwrap();
+    host.idle_timeout = Duration::from_millis(100);
+    // Says hello, then neither sends nor closes the connection
+    let mut quiet = TcpStream::connect(host.local_addr().unwrap()).unwrap();
+    let hello = Message::Hello {
+        version: PROTOCOL_VERSION,
+        config_hash: config.gameplay_hash(),
+    }
+    .to_bytes();
+    quiet
+        .write_all(&(hello.len() as u32).to_le_bytes())
+        .unwrap();
+    quiet.write_all(&hello).unwrap();
+
+    let mut events = Vec::new();
+    wait_until(|| {
+        events.extend(host.poll());
+        events.len() == 2
+    });
+    assert_eq!(events[0], HostEvent::Joined);
+    assert!(matches!(events[1], HostEvent::Left(_)));
+    assert!(!host.is_connected());
+}
+
+#[test]
+fn test_client_times_out_on_a_silent_host() {
+    // Takes the connection but never answers
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut client =
+        ClientSession::connect(listener.local_addr().unwrap(), &match_config()).unwrap();
+    client.idle_timeout = Duration::from_millis(100);
+    let mut result = Ok(Vec::new());
+    wait_until(|| {
+        result = client.poll(Instant::now());
+        result.is_err()
+    });
+    assert!(matches!(result, Err(NetError::TimedOut)));
+}
+
+#[test]
+fn test_second_joiner_is_turned_away() {
+    let config = match_config();
+    let mut host = HostSession::bind("127.0.0.1:0", &config).unwrap();
+    let mut first = ClientSession::connect(host.local_addr().unwrap(), &config).unwrap();
+    wait_until(|| {
+        host.poll();
+        first.poll(Instant::now()).unwrap();
+        first.is_welcomed()
+    });
+
+    let mut second = ClientSession::connect(host.local_addr().unwrap(), &config).unwrap();
+    let mut result = Ok(Vec::new());
+    wait_until(|| {
+        host.poll();
+        result = second.poll(Instant::now());
+        result.is_err()
+    });
+    assert!(matches!(result, Err(NetError::Rejected(reason)) if reason == "match in progress"));
+    assert!(host.is_connected());
+    assert!(first.poll(Instant::now()).is_ok());
+}
+
+#[test]
+fn test_host_rejects_other_config() {
+    let mut host = HostSession::bind("127.0.0.1:0", &match_config()).unwrap();
+    let faster = GameConfig {
+        ball_speed: 900.0,
+        ..match_config()
+    };
+    let mut client = ClientSession::connect(host.local_addr().unwrap(), &faster).unwrap();
+    let mut result = Ok(Vec::new());
+    wait_until(|| {
+        assert!(host.poll().is_empty());
+        result = client.poll(Instant::now());
+        result.is_err()
+    });
+    assert!(matches!(result, Err(NetError::Rejected(_))));
+    assert!(!host.is_connected());
+}
+
+#[test]
+fn test_a_flood_of_messages_is_read_over_several_calls() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut sender = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let mut connection = Connection::new(listener.accept().unwrap().0).unwrap();
+    let input = Message::Input(Input {
+        left: true,
+        ..Input::default()
+    })
+    .to_bytes();
+    let frame_len = 4 + input.len();
+    let total = 2 * MAX_FRAME / frame_len;
+    let mut flood = Vec::new();
+    for _ in 0..total {
+        flood.extend_from_slice(&(input.len() as u32).to_le_bytes());
+        flood.extend_from_slice(&input);
+    }
+    sender.write_all(&flood).unwrap();
+    thread::sleep(Duration::from_millis(50));
+
+    // Everything has arrived, but one call only takes about a frame's worth
+    let first = connection.receive().unwrap().len();
+    assert!(first > 0);
+    assert!(first * frame_len <= MAX_FRAME + 4096);
+    let mut received = first;
+    wait_until(|| {
+        received += connection.receive().unwrap().len();
+        received == total
+    });
+}
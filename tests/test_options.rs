@@ -0,0 +1,101 @@
+use bounce_shield::LaunchOptions;
+use std::path::Path;
+
+fn parse(args: &[&str]) -> Result<LaunchOptions, String> {
+    LaunchOptions::parse(args.iter().map(|arg| arg.to_string()))
+}
+
+#[test]
+fn test_no_arguments() {
+    assert_eq!(parse(&[]).unwrap(), LaunchOptions::default());
+}
+
+#[test]
+fn test_seed_flag() {
+    let options = parse(&["--seed", "1234"]).unwrap();
+    assert_eq!(options.seed, Some(1234));
+    assert_eq!(options.seed_or_random(), 1234);
+}
+
+#[test]
+fn test_config_flag() {
+    let options = parse(&["--config", "tuning/fast.toml", "--seed", "9"]).unwrap();
+    assert_eq!(options.config, Some("tuning/fast.toml".into()));
+    assert_eq!(options.seed, Some(9));
+}
+
+#[test]
+fn test_two_player_flag() {
+    let options = parse(&["--two-player"]).unwrap();
+    assert!(options.two_player);
+    assert!(options.game_config().unwrap().two_player);
+}
+
+#[test]
+fn test_demo_flag() {
+    assert!(parse(&["--demo"]).unwrap().demo);
+}
+
+#[test]
+fn test_no_shake_flag() {
+    let options = parse(&["--no-shake"]).unwrap();
+    assert!(!options.game_config().unwrap().screen_shake);
+    assert!(parse(&[]).unwrap().game_config().unwrap().screen_shake);
+}
+
+#[test]
+fn test_levels_flag() {
+    let options = parse(&["--levels", "stages"]).unwrap();
+    assert_eq!(
+        options.game_config().unwrap().levels_dir,
+        Path::new("stages")
+    );
+    assert_eq!(
+        parse(&[]).unwrap().game_config().unwrap().levels_dir,
+        Path::new("levels")
+    );
+    assert!(parse(&["--levels"]).is_err());
+}
+
+#[test]
+fn test_replay_flag() {
+    let options = parse(&["--replay", "run-42.bsr"]).unwrap();
+    assert_eq!(options.replay, Some("run-42.bsr".into()));
+}
+
+#[test]
+fn test_bad_arguments() {
+    assert!(parse(&["--seed"]).is_err());
+    assert!(parse(&["--seed", "soon"]).is_err());
+    assert!(parse(&["--config"]).is_err());
+    assert!(parse(&["--replay"]).is_err());
+    assert!(parse(&["--turbo"]).is_err());
+}
+
+#[test]
+fn test_network_flags() {
+    let host = parse(&["--host", "0.0.0.0"]).unwrap();
+    assert_eq!(host.host.as_deref(), Some("0.0.0.0:7777"));
+    assert!(host.game_config().unwrap().two_player);
+
+    let join = parse(&["--join", "localhost:9000"]).unwrap();
+    assert_eq!(join.join.as_deref(), Some("localhost:9000"));
+    assert!(join.game_config().unwrap().two_player);
+
+    // IPv6 addresses with and without a port
+    for (addr, expected) in [
+        ("::1", "[::1]:7777"),
+        ("[::1]", "[::1]:7777"),
+        ("[::1]:9000", "[::1]:9000"),
+        ("fe80::1", "[fe80::1]:7777"),
+    ] {
+        let join = parse(&["--join", addr]).unwrap();
+        assert_eq!(join.join.as_deref(), Some(expected));
+    }
+
+    assert!(parse(&["--host"]).is_err());
+    assert!(parse(&["--host", "a", "--join", "b"]).is_err());
+
+    assert!(parse(&["--headless", "--join", "b"]).unwrap().headless);
+    assert!(parse(&["--headless"]).is_err());
+}
@@ -0,0 +1,86 @@
+use bounce_shield::{GameConfig, Input, MAX_PARTICLES, Particles, SimEvent, Simulation, Surface};
+use ggez::graphics::Color;
+
+#[test]
+fn test_burst_sprays_along_the_normal() {
+    let mut particles = Particles::default();
+    particles.burst(100.0, 200.0, [0.0, -1.0], 20, Color::GREEN);
+    assert_eq!(particles.alive().len(), 20);
+    for particle in particles.alive() {
+        assert_eq!((particle.x, particle.y), (100.0, 200.0));
+        assert!(particle.dy < 0.0);
+    }
+}
+
+#[test]
+fn test_particles_fade_and_expire() {
+    let mut particles = Particles::default();
+    particles.explode(0.0, 0.0, 30, Color::RED);
+    particles.update(0.1);
+    assert!(particles.alive().iter().all(|particle| {
+        let alpha = particle.current_color().a;
+        alpha < 1.0 && alpha > 0.0
+    }));
+    particles.update(2.0);
+    assert!(particles.alive().is_empty());
+}
+
+#[test]
+fn test_pool_never_grows() {
+    let mut particles = Particles::new(50);
+    for _ in 0..10 {
+        particles.explode(0.0, 0.0, 40, Color::RED);
+    }
+    assert_eq!(particles.alive().len(), 50);
+    assert_eq!(Particles::default().capacity(), MAX_PARTICLES);
+}
+
+#[test]
+fn test_wall_and_bar_hits_report_impacts() {
+    let mut sim = Simulation::new(GameConfig::default(), 1);
+    let ball = &mut sim.balls[0];
+    ball.x = 1.0;
+    ball.dx = -300.0;
+    sim.step(Input::default());
+    assert!(sim.drain_events().iter().any(|event| matches!(
+        event,
+        SimEvent::Impact {
+            x: 0.0,
+            normal: [1.0, 0.0],
+            surface: Surface::Wall,
+            ..
+        }
+    )));
+
+    let (bar_x, bar_y, bar_width) = (sim.bar.x, sim.bar.y, sim.bar.width);
+    let ball = &mut sim.balls[0];
+    ball.x = bar_x + (bar_width - ball.size) / 2.0;
+    ball.y = bar_y - ball.size - 1.0;
+    ball.dx = 0.0;
+    ball.dy = 300.0;
+    sim.step(Input::default());
+    let events = sim.drain_events();
+    assert!(events.contains(&SimEvent::BarHit));
+    assert!(events.iter().any(|event| matches!(
+        event,
+        SimEvent::Impact {
+            normal: [0.0, -1.0],
+            surface: Surface::Bar,
+            y,
+            ..
+        } if *y == bar_y
+    )));
+}
+
+#[test]
+fn test_dropped_ball_is_reported_where_it_left() {
+    let mut sim = Simulation::new(GameConfig::default(), 1);
+    sim.balls[0].x = 300.0;
+    sim.balls[0].y = sim.config.height + 1.0;
+    sim.step(Input::default());
+    let centre = sim.config.ball_size / 2.0 + 300.0;
+    assert!(sim.drain_events().iter().any(|event| matches!(
+        event,
+        SimEvent::BallLost { x, y } if (x - centre).abs() < 10.0 && *y == sim.config.height
+    )));
+}
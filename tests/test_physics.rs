@@ -0,0 +1,67 @@
+use bounce_shield::{Aabb, paddle_bounce, reflect, sweep};
+
+#[test]
+fn test_sweep_hits_box_in_path() {
+    let ball = Aabb::new(0.0, 0.0, 10.0, 10.0);
+    let wall = Aabb::new(50.0, -100.0, 10.0, 200.0);
+
+    let hit = sweep(ball, [80.0, 0.0], wall).unwrap();
+
+    assert!((hit.time - 0.5).abs() < 1e-6);
+    assert_eq!(hit.normal, [-1.0, 0.0]);
+}
+
+#[test]
+fn test_sweep_catches_fast_movers() {
+    // Moves far past a thin box in a single step without ever overlapping it at either end
+    let ball = Aabb::new(0.0, 0.0, 10.0, 10.0);
+    let bar = Aabb::new(-50.0, 500.0, 100.0, 5.0);
+
+    let hit = sweep(ball, [0.0, 10_000.0], bar).unwrap();
+
+    assert!(hit.time > 0.0 && hit.time < 0.1);
+    assert_eq!(hit.normal, [0.0, -1.0]);
+}
+
+#[test]
+fn test_sweep_misses() {
+    let ball = Aabb::new(0.0, 0.0, 10.0, 10.0);
+    let target = Aabb::new(100.0, 100.0, 10.0, 10.0);
+
+    assert_eq!(sweep(ball, [50.0, 0.0], target), None);
+    assert_eq!(sweep(ball, [-200.0, -200.0], target), None);
+    // Moving away from something it is touching
+    let touching = Aabb::new(10.0, 0.0, 10.0, 10.0);
+    assert_eq!(sweep(ball, [-5.0, 0.0], touching), None);
+}
+
+#[test]
+fn test_reflect_only_flips_incoming_component() {
+    assert_eq!(reflect([3.0, 4.0], [0.0, -1.0]), [3.0, -4.0]);
+    assert_eq!(reflect([3.0, -4.0], [0.0, -1.0]), [3.0, -4.0]);
+    assert_eq!(reflect([3.0, 4.0], [-1.0, 0.0]), [-3.0, 4.0]);
+}
+
+#[test]
+fn test_paddle_bounce_angle_follows_offset() {
+    let max_angle = 60f32.to_radians();
+    let incoming = [300.0, 400.0];
+
+    let center = paddle_bounce(incoming, 0.0, max_angle, 0.0);
+    assert!(center[0].abs() < 1e-3);
+    assert!((center[1] + 500.0).abs() < 1e-3);
+
+    let right = paddle_bounce(incoming, 1.0, max_angle, 0.0);
+    let left = paddle_bounce(incoming, -1.0, max_angle, 0.0);
+    assert!((right[0].atan2(-right[1]) - max_angle).abs() < 1e-4);
+    assert!((left[0] + right[0]).abs() < 1e-3);
+    // Offsets past the edge are treated as the edge
+    assert_eq!(paddle_bounce(incoming, 3.0, max_angle, 0.0), right);
+}
+
+#[test]
+fn test_paddle_bounce_minimum_vertical_speed() {
+    let out = paddle_bounce([500.0, 10.0], 1.0, 85f32.to_radians(), 200.0);
+    assert_eq!(out[1], -200.0);
+    assert!(out[0] > 0.0);
+}
@@ -0,0 +1,218 @@
+use bounce_shield::{
+    GameConfig, Input, MAX_BALLS, MAX_POWER_UPS, MULTI_BALL_EXTRA, POWERUP_DURATION_TICKS, PowerUp,
+    PowerUpType, SLOW_BALL_FACTOR, SimEvent, Simulation, TICK_DT, WIDTH_INCREASE_FACTOR,
+};
+
+fn sim(seed: u64) -> Simulation {
+    Simulation::new(GameConfig::default(), seed)
+}
+
+// Drop a pickup right where the ball will be next tick
+fn place_under_ball(sim: &mut Simulation, power_type: PowerUpType) {
+    sim.power_ups.push(PowerUp::new(
+        sim.balls[0].x - 5.0,
+        sim.balls[0].y - 5.0,
+        power_type,
+    ));
+}
+
+#[test]
+fn test_pickup_is_removed_when_collected() {
+    let mut sim = sim(1);
+    place_under_ball(&mut sim, PowerUpType::WidthIncrease);
+    sim.step(Input::default());
+    assert!(sim.power_ups.is_empty());
+    assert!(
+        sim.drain_events()
+            .contains(&SimEvent::PowerUpCollected(PowerUpType::WidthIncrease))
+    );
+}
+
+#[test]
+fn test_width_increase_expires_and_restores_bar() {
+    let mut sim = sim(1);
+    let centre = sim.bar.x + sim.bar.width / 2.0;
+    place_under_ball(&mut sim, PowerUpType::WidthIncrease);
+    sim.step(Input::default());
+    assert_eq!(sim.bar.width, sim.config.bar_width * WIDTH_INCREASE_FACTOR);
+    assert_eq!(sim.bar.x + sim.bar.width / 2.0, centre);
+
+    sim.drain_events();
+    sim.tick += POWERUP_DURATION_TICKS;
+    sim.expire_effects();
+    assert_eq!(sim.bar.width, sim.config.bar_width);
+    assert!(sim.effects.is_empty());
+    assert_eq!(
+        sim.drain_events(),
+        vec![SimEvent::PowerUpExpired(PowerUpType::WidthIncrease)]
+    );
+}
+
+#[test]
+fn test_speed_boost_expires() {
+    let mut sim = sim(1);
+    place_under_ball(&mut sim, PowerUpType::SpeedBoost);
+    sim.step(Input::default());
+    assert!(sim.bar.speed > sim.config.bar_speed);
+
+    sim.tick += POWERUP_DURATION_TICKS;
+    sim.expire_effects();
+    assert_eq!(sim.bar.speed, sim.config.bar_speed);
+}
+
+#[test]
+fn test_collecting_again_restarts_timer_without_stacking() {
+    let mut sim = sim(1);
+    place_under_ball(&mut sim, PowerUpType::WidthIncrease);
+    sim.step(Input::default());
+    sim.tick += POWERUP_DURATION_TICKS / 2;
+    place_under_ball(&mut sim, PowerUpType::WidthIncrease);
+    sim.step(Input::default());
+
+    assert_eq!(sim.effects.len(), 1);
+    assert_eq!(sim.bar.width, sim.config.bar_width * WIDTH_INCREASE_FACTOR);
+    assert_eq!(
+        sim.effect_ticks_left(PowerUpType::WidthIncrease),
+        Some(POWERUP_DURATION_TICKS)
+    );
+}
+
+#[test]
+fn test_extra_heart_is_instant() {
+    let mut sim = sim(1);
+    let hearts = sim.hearts;
+    place_under_ball(&mut sim, PowerUpType::ExtraHeart);
+    sim.step(Input::default());
+    assert_eq!(sim.hearts, hearts + 1);
+    assert!(sim.effects.is_empty());
+}
+
+#[test]
+fn test_slow_ball_slows_ball_until_it_expires() {
+    let mut sim = sim(1);
+    place_under_ball(&mut sim, PowerUpType::SlowBall);
+    sim.step(Input::default());
+
+    let y = sim.balls[0].y;
+    let dy = sim.balls[0].dy;
+    sim.step(Input::default());
+    assert!((sim.balls[0].y - (y + dy * TICK_DT * SLOW_BALL_FACTOR)).abs() < 1e-3);
+
+    sim.tick += POWERUP_DURATION_TICKS;
+    sim.expire_effects();
+    assert_eq!(sim.ball_time_scale(), 1.0);
+}
+
+#[test]
+fn test_multi_ball_splits_the_ball() {
+    let mut sim = sim(1);
+    place_under_ball(&mut sim, PowerUpType::MultiBall);
+    sim.step(Input::default());
+    assert_eq!(sim.balls.len(), 1 + MULTI_BALL_EXTRA);
+    assert!(sim.effects.is_empty());
+
+    // Same speed, different directions
+    let speed = |dx: f32, dy: f32| (dx * dx + dy * dy).sqrt();
+    let first = sim.balls[0];
+    for ball in &sim.balls[1..] {
+        assert!((speed(ball.dx, ball.dy) - speed(first.dx, first.dy)).abs() < 1e-2);
+        assert_ne!((ball.dx, ball.dy), (first.dx, first.dy));
+    }
+}
+
+#[test]
+fn test_extra_balls_are_capped() {
+    let mut sim = sim(1);
+    sim.spawn_extra_balls(100);
+    assert_eq!(sim.balls.len(), MAX_BALLS);
+}
+
+#[test]
+fn test_heart_is_lost_only_with_the_last_ball() {
+    let mut sim = sim(1);
+    let hearts = sim.hearts;
+    sim.spawn_extra_balls(1);
+    sim.balls[0].y = sim.config.height + 1.0;
+    sim.step(Input::default());
+    assert_eq!(sim.balls.len(), 1);
+    assert_eq!(sim.hearts, hearts);
+    assert!(
+        !sim.drain_events()
+            .iter()
+            .any(|e| matches!(e, SimEvent::HeartLost { .. }))
+    );
+
+    sim.balls[0].y = sim.config.height + 1.0;
+    sim.step(Input::default());
+    assert_eq!(sim.hearts, hearts - 1);
+    assert_eq!(sim.balls.len(), 1);
+}
+
+#[test]
+fn test_spawn_chance_and_limit() {
+    let mut off = Simulation::new(
+        GameConfig {
+            power_up_chance: 0.0,
+            ..GameConfig::default()
+        },
+        1,
+    );
+    for _ in 0..50 {
+        off.spawn_power_up();
+    }
+    assert!(off.power_ups.is_empty());
+
+    let mut always = Simulation::new(
+        GameConfig {
+            power_up_chance: 1.0,
+            ..GameConfig::default()
+        },
+        1,
+    );
+    for _ in 0..50 {
+        always.spawn_power_up();
+    }
+    assert_eq!(always.power_ups.len(), MAX_POWER_UPS);
+    for power_up in &always.power_ups {
+        assert!(power_up.x >= 0.0 && power_up.x + power_up.size <= always.config.width);
+        assert!(power_up.y >= 0.0 && power_up.y + power_up.size <= always.config.height / 2.0);
+    }
+}
+
+#[test]
+fn test_power_ups_do_not_change_ball_respawns() {
+    let config = |chance| GameConfig {
+        power_up_chance: chance,
+        ..GameConfig::default()
+    };
+    let mut with = Simulation::new(config(1.0), 9);
+    let mut without = Simulation::new(config(0.0), 9);
+    with.spawn_power_up();
+    with.reset_with_seed(10);
+    without.reset_with_seed(10);
+    assert_eq!(
+        (with.balls[0].dx, with.balls[0].dy),
+        (without.balls[0].dx, without.balls[0].dy)
+    );
+    assert!(with.power_ups.is_empty());
+}
+
+#[test]
+fn test_invalid_power_up_chance_is_rejected() {
+    assert!(GameConfig::from_toml("power_up_chance = 1.5\n").is_err());
+}
+
+#[test]
+fn test_tiny_field_skips_power_ups() {
+    let config = GameConfig {
+        width: 30.0,
+        bar_width: 20.0,
+        ball_size: 5.0,
+        power_up_chance: 1.0,
+        ..GameConfig::default()
+    };
+    assert!(config.validate().is_ok());
+    let mut sim = Simulation::new(config, 1);
+    sim.spawn_power_up();
+    assert!(sim.power_ups.is_empty());
+}
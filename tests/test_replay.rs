@@ -0,0 +1,210 @@
+mod common;
+
+use bounce_shield::{
+    Game, GameConfig, GameState, HIGH_SCORES_FILE, Input, REPLAY_DIR, Replay, ReplayError,
+    ReplayPlayer, Simulation, TICK_DT,
+};
+use common::TempDir;
+use std::fs;
+use std::time::Duration;
+
+// Play a scripted run while recording it, as the game loop does
+fn record_run(seed: u64) -> (Simulation, Replay) {
+    let mut sim = Simulation::new(GameConfig::default(), seed);
+    let mut replay = Replay::start(&sim);
+    let mut tick = 0u32;
+    while sim.state == GameState::Playing && tick < 20_000 {
+        let input = Input {
+            left: tick % 120 < 50,
+            right: tick % 120 >= 70,
+            axis: ((tick / 60 % 5) as i32 * 50 - 100) as i8,
+            target: (tick / 90)
+                .is_multiple_of(3)
+                .then_some(400 + (tick / 90 % 7) as u16 * 200),
+        };
+        replay.record(input);
+        sim.step(input);
+        tick += 1;
+    }
+    (sim, replay)
+}
+
+#[test]
+fn test_input_bits_round_trip() {
+    for bits in 0..4 {
+        assert_eq!(Input::from_bits(bits).to_bits(), bits);
+    }
+    assert_eq!(Input::default().to_bits(), 0);
+}
+
+#[test]
+fn test_replay_reproduces_run() {
+    let (original, replay) = record_run(2024);
+
+    let bytes = replay.to_bytes();
+    let loaded = Replay::from_bytes(&bytes).unwrap();
+    assert_eq!(loaded, replay);
+
+    let replayed = loaded.simulate(GameConfig::default()).unwrap();
+    assert_eq!(replayed.balls, original.balls);
+    assert_eq!(replayed.bar.x, original.bar.x);
+    assert_eq!(
+        (replayed.score, replayed.hearts, replayed.state),
+        (original.score, original.hearts, original.state)
+    );
+}
+
+#[test]
+fn test_replay_is_compact() {
+    let (_, replay) = record_run(7);
+    // Held keys compress into runs instead of one byte per tick
+    assert!(replay.to_bytes().len() < replay.inputs.len() / 4 + 64);
+}
+
+#[test]
+fn test_replay_keeps_session_state() {
+    let mut sim = Simulation::new(GameConfig::default(), 11);
+    sim.high_score = 30;
+    sim.prev_high_score = 25;
+    sim.reset();
+    let replay = Replay::start(&sim);
+
+    let restored = Replay::from_bytes(&replay.to_bytes())
+        .unwrap()
+        .simulate(GameConfig::default())
+        .unwrap();
+    assert_eq!(restored.seed, sim.seed);
+    assert_eq!((restored.high_score, restored.prev_high_score), (30, 25));
+    assert!(!restored.first_start);
+}
+
+#[test]
+fn test_replay_rejects_other_config() {
+    let (_, replay) = record_run(3);
+    let faster = GameConfig {
+        ball_speed: 900.0,
+        ..GameConfig::default()
+    };
+    assert!(matches!(
+        replay.simulate(faster),
+        Err(ReplayError::ConfigMismatch)
+    ));
+}
+
+#[test]
+fn test_replay_rejects_bad_files() {
+    let (_, replay) = record_run(5);
+    let bytes = replay.to_bytes();
+
+    assert!(matches!(
+        Replay::from_bytes(b"PNG\x00 nope"),
+        Err(ReplayError::NotAReplay)
+    ));
+    assert!(matches!(
+        Replay::from_bytes(&bytes[..bytes.len() - 2]),
+        Err(ReplayError::Truncated)
+    ));
+    let mut future = bytes.clone();
+    future[4] = 200;
+    assert!(matches!(
+        Replay::from_bytes(&future),
+        Err(ReplayError::UnsupportedVersion(200))
+    ));
+}
+
+#[test]
+fn test_replay_rejects_runs_that_dont_add_up() {
+    let mut replay = Replay::start(&Simulation::new(GameConfig::default(), 8));
+    for _ in 0..10 {
+        replay.record(Input::default());
+    }
+    let bytes = replay.to_bytes();
+    // One run of 10 ticks: bits, axis and a two-byte length after the header
+    let length_at = bytes.len() - 2;
+
+    let mut empty_run = bytes.clone();
+    empty_run[length_at..].copy_from_slice(&0u16.to_le_bytes());
+    assert!(matches!(
+        Replay::from_bytes(&empty_run),
+        Err(ReplayError::Corrupt)
+    ));
+    let mut too_long = bytes.clone();
+    too_long[length_at..].copy_from_slice(&u16::MAX.to_le_bytes());
+    assert!(matches!(
+        Replay::from_bytes(&too_long),
+        Err(ReplayError::Corrupt)
+    ));
+
+    // A header claiming far more ticks than the runs could hold
+    let mut huge = bytes.clone();
+    huge[38..42].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(
+        Replay::from_bytes(&huge),
+        Err(ReplayError::Truncated)
+    ));
+}
+
+#[test]
+fn test_player_feeds_inputs_in_order() {
+    let mut replay = Replay::start(&Simulation::new(GameConfig::default(), 1));
+    replay.record(Input {
+        left: true,
+        right: false,
+        ..Input::default()
+    });
+    replay.record(Input::default());
+
+    let mut player = ReplayPlayer::new(replay);
+    assert!(player.next_input().unwrap().left);
+    assert_eq!(player.next_input(), Some(Input::default()));
+    assert!(player.is_finished());
+    assert_eq!(player.next_input(), None);
+}
+
+#[test]
+fn test_input_bytes_round_trip() {
+    let input = Input {
+        left: true,
+        right: false,
+        axis: -40,
+        target: Some(900),
+    };
+    let mut bytes = Vec::new();
+    input.write_bytes(&mut bytes);
+    assert_eq!(Input::read_bytes(&mut bytes.as_slice()), Some(input));
+    assert_eq!(Input::read_bytes(&mut &bytes[..bytes.len() - 1]), None);
+}
+
+#[test]
+fn test_taking_over_a_replay_leaves_it_alone() {
+    let dir = TempDir::new();
+    let (_, mut replay) = record_run(31);
+    replay.inputs.truncate(300);
+    let path = dir.join(REPLAY_DIR).join(replay.file_name());
+    replay.save(&path).unwrap();
+    let original = fs::read(&path).unwrap();
+
+    let mut game = Game::headless(GameConfig::default(), 31, &dir);
+    game.play_replay(Replay::load(&path).unwrap()).unwrap();
+    game.sim.state = GameState::Playing;
+    let idle = (Input::default(), Input::default());
+    while game.sim.state == GameState::Playing {
+        game.frame(Duration::from_secs_f32(TICK_DT), idle);
+    }
+    assert!(game.playback.is_none());
+    assert_eq!(game.sim.state, GameState::Paused);
+
+    // Carry on from where the replay ran out until the run is lost
+    game.sim.state = GameState::Playing;
+    while game.sim.state == GameState::Playing {
+        game.frame(Duration::from_secs_f32(TICK_DT), idle);
+    }
+    assert_eq!(game.sim.state, GameState::GameOver);
+    assert_eq!(fs::read(&path).unwrap(), original);
+    assert!(
+        dir.join(REPLAY_DIR)
+            .join(replay.takeover_file_name())
+            .exists()
+    );
+    assert!(!dir.join(HIGH_SCORES_FILE).exists());
+}
@@ -0,0 +1,20 @@
+use bounce_shield::{MUSIC, Sound, SoundBank};
+
+// The clips are built in, so this checks they are real WAV files
+#[test]
+fn test_every_sound_is_a_wav_file() {
+    for data in Sound::ALL.iter().map(|sound| sound.data()).chain([MUSIC]) {
+        assert!(data.len() > 44);
+        assert_eq!(&data[..4], b"RIFF");
+        assert_eq!(&data[8..12], b"WAVE");
+    }
+}
+
+#[test]
+fn test_empty_bank_is_silent() {
+    let mut bank = SoundBank::default();
+    assert!(Sound::ALL.iter().all(|&sound| !bank.is_loaded(sound)));
+    assert!(!bank.has_music());
+    bank.play(Sound::Bounce);
+    bank.start_music();
+}
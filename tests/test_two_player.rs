@@ -0,0 +1,162 @@
+use bounce_shield::{
+    BAR_MARGIN, GameConfig, GameState, Input, Player, Replay, SimEvent, Simulation,
+};
+
+fn match_config() -> GameConfig {
+    GameConfig {
+        two_player: true,
+        brick_rows: 2,
+        power_up_chance: 1.0,
+        ..GameConfig::default()
+    }
+}
+
+fn sim(seed: u64) -> Simulation {
+    Simulation::new(match_config(), seed)
+}
+
+const LEFT: Input = Input {
+    left: true,
+    right: false,
+    axis: 0,
+    target: None,
+};
+
+#[test]
+fn test_gamepads_go_to_the_player_without_the_keyboard() {
+    assert_eq!(Player::for_gamepad(0, false), Player::One);
+    assert_eq!(Player::for_gamepad(1, false), Player::Two);
+    // Player one on the keyboard: the only gamepad steers the top bar
+    assert_eq!(Player::for_gamepad(0, true), Player::Two);
+    assert_eq!(Player::for_gamepad(1, true), Player::One);
+}
+
+#[test]
+fn test_second_bar_only_in_two_player_mode() {
+    assert!(
+        Simulation::new(GameConfig::default(), 1)
+            .player_two
+            .is_none()
+    );
+    let sim = sim(1);
+    let player_two = sim.player_two.as_ref().unwrap();
+    assert_eq!(player_two.bar.y, BAR_MARGIN);
+    assert_eq!(player_two.hearts, sim.config.initial_hearts);
+    // Matches are played on an empty field, serving from the middle to player one
+    assert!(sim.bricks.is_empty());
+    assert!(!sim.has_stages());
+    assert_eq!(
+        sim.balls[0].y,
+        (sim.config.height - sim.config.ball_size) / 2.0
+    );
+    assert!(sim.balls[0].dy > 0.0);
+}
+
+#[test]
+fn test_each_player_steers_their_own_bar() {
+    let mut sim = sim(1);
+    let (bottom, top) = (sim.bar.x, sim.player_two.as_ref().unwrap().bar.x);
+    sim.step_players(Input::default(), LEFT);
+    assert_eq!(sim.bar.x, bottom);
+    assert!(sim.player_two.as_ref().unwrap().bar.x < top);
+
+    sim.step_players(LEFT, Input::default());
+    assert!(sim.bar.x < bottom);
+}
+
+#[test]
+fn test_top_bar_sends_ball_back_down_and_scores() {
+    let mut sim = sim(1);
+    let bar = &sim.player_two.as_ref().unwrap().bar;
+    let (x, y) = (bar.x + bar.width / 2.0, bar.y + bar.height + 1.0);
+    let ball = &mut sim.balls[0];
+    ball.x = x - ball.size / 2.0;
+    ball.y = y;
+    ball.dx = 0.0;
+    ball.dy = -300.0;
+    sim.step_players(Input::default(), Input::default());
+    assert!(sim.balls[0].dy > 0.0);
+    assert_eq!(sim.player_two.as_ref().unwrap().score, 1);
+    assert_eq!(sim.score, 0);
+    assert!(sim.drain_events().contains(&SimEvent::BarHit));
+}
+
+#[test]
+fn test_ball_past_the_top_costs_player_two_a_heart() {
+    let mut sim = sim(1);
+    let ball = &mut sim.balls[0];
+    ball.x = 0.0;
+    ball.y = -ball.size - 1.0;
+    ball.dy = -300.0;
+    sim.step_players(Input::default(), Input::default());
+
+    assert_eq!(sim.hearts, sim.config.initial_hearts);
+    assert_eq!(
+        sim.player_two.as_ref().unwrap().hearts,
+        sim.config.initial_hearts - 1
+    );
+    assert!(sim.drain_events().contains(&SimEvent::HeartLost {
+        player: Player::Two,
+        remaining: sim.config.initial_hearts - 1,
+    }));
+    // The next serve heads for the player who lost the point
+    assert!(sim.balls[0].dy < 0.0);
+}
+
+#[test]
+fn test_game_over_names_the_winner() {
+    let mut sim = sim(1);
+    assert_eq!(sim.winner(), None);
+    sim.hearts = 1;
+    sim.balls[0].y = sim.config.height + 1.0;
+    sim.step_players(Input::default(), Input::default());
+    assert_eq!(sim.state, GameState::GameOver);
+    assert_eq!(sim.winner(), Some(Player::Two));
+    assert_eq!(Simulation::new(GameConfig::default(), 1).winner(), None);
+}
+
+#[test]
+fn test_matches_skip_power_ups_and_high_scores() {
+    let mut sim = sim(1);
+    sim.spawn_power_up();
+    assert!(sim.power_ups.is_empty());
+    sim.score = 50;
+    sim.check_high_score();
+    assert_eq!(sim.high_score, 0);
+}
+
+#[test]
+fn test_two_player_replay_reproduces_match() {
+    let mut sim = sim(7);
+    let mut replay = Replay::start(&sim);
+    for tick in 0..3_000u32 {
+        if sim.state != GameState::Playing {
+            break;
+        }
+        let one = Input {
+            right: tick % 80 < 30,
+            ..Input::default()
+        };
+        let two = Input {
+            left: tick % 50 < 20,
+            ..Input::default()
+        };
+        replay.record(one);
+        replay.record_player_two(two);
+        sim.step_players(one, two);
+    }
+
+    let loaded = Replay::from_bytes(&replay.to_bytes()).unwrap();
+    assert_eq!(loaded, replay);
+    let replayed = loaded.simulate(match_config()).unwrap();
+    assert_eq!(replayed.balls, sim.balls);
+    let (a, b) = (
+        replayed.player_two.as_ref().unwrap(),
+        sim.player_two.as_ref().unwrap(),
+    );
+    assert_eq!((a.bar.x, a.score, a.hearts), (b.bar.x, b.score, b.hearts));
+    assert_eq!((replayed.score, replayed.hearts), (sim.score, sim.hearts));
+
+    // A match can't be played back as a one-player game
+    assert!(loaded.simulate(GameConfig::default()).is_err());
+}